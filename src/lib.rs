@@ -0,0 +1,10 @@
+pub mod tsp_bench;
+pub mod tsp_compare;
+pub mod tsp_constructors;
+pub mod tsp_data;
+pub mod tsp_observers;
+pub mod tsp_render;
+pub mod tsp_results;
+pub mod tsp_scoring;
+pub mod tsp_solvers;
+pub mod tsp_tuning;
@@ -1,11 +1,72 @@
-mod tsp_data;
-mod tsp_solvers;
+use std::{
+    collections::HashMap,
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, IsTerminal, Write},
+    ops::RangeInclusive,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex, OnceLock},
+    time::Duration,
+};
+use tracing::{error, info, info_span, warn};
+use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+use tsp_swarm_solver::tsp_bench::{BenchCase, BenchReport, trial_seeds};
+use tsp_swarm_solver::tsp_compare::ComparisonReport;
+use tsp_swarm_solver::tsp_constructors::Construction;
+use tsp_swarm_solver::tsp_data::{TspData, read_answers, read_tour};
+use tsp_swarm_solver::tsp_observers::{
+    HistoryRecorder, LogObserver, MetricsObserver, SolverObserver,
+};
+use tsp_swarm_solver::tsp_render::{FrameRecorder, RenderConfig, Stroke, TourPlot, render_frames};
+use tsp_swarm_solver::tsp_results::{ResultSet, RunResult};
+use tsp_swarm_solver::tsp_scoring::{
+    Grade, Reference, Rubric, ScoreRow, ScoreTable, Threshold, one_tree_bound,
+};
+use tsp_swarm_solver::tsp_solvers::{
+    AbcConfig, AbcTspSolver, AcsConfig, AcsTspSolver, CancelToken, Checkpointing,
+    ConstructionConfig, ConstructionTspSolver, DiscretePsoConfig, DiscretePsoTspSolver,
+    FireflyConfig, FireflyTspSolver, GaConfig, GaTspSolver, IlsConfig, IlsTspSolver,
+    InertiaSchedule, MmasConfig, MmasTspSolver, PsoConfig, PsoTspSolver, SaConfig, SaTspSolver,
+    TspSolver, UpdateRule,
+};
+use tsp_swarm_solver::tsp_tuning::{ParamSpace, TunerConfig, TuningResult, tune};
 
-use ::colored::Colorize;
-use std::{fs, path::Path};
-use tsp_data::TspData;
-use tsp_solvers::{AcsTspSolver, PsoTspSolver};
+// Взводится по Ctrl-C: текущий солвер возвращает лучший тур, остальные тесты пропускаются
+static CANCEL: LazyLock<CancelToken> = LazyLock::new(CancelToken::new);
 
+// Каталог для истории сходимости (`--history <dir>`), если задан
+static HISTORY_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+// Каталог для кадров анимации улучшений (`--frames <dir>`), если задан
+static FRAMES_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+// Режимы командной строки: аргумент после `--history`/`--frames` с таким именем -- не каталог
+const MODES: &[&str] = &[
+    "mmas",
+    "elitist",
+    "rank",
+    "multistart",
+    "checkpoint",
+    "warm",
+    "warm-pso",
+    "dpso",
+    "hpso",
+    "ga",
+    "sa",
+    "ils",
+    "abc",
+    "firefly",
+    "bench",
+    "compare",
+    "tune",
+    "verify",
+    "results",
+    "render",
+    "construct",
+];
+
+#[derive(Clone, Copy)]
 struct SolverConfig {
     n_ants: usize,
     n_iterations: usize,
@@ -15,116 +76,1132 @@ struct SolverConfig {
 
 struct TestsConfig<'a> {
     filename: &'a str,
-    score_min: f64,
-    score_max: f64,
+    rubric: Rubric,
     solver_configs: SolverConfig,
 }
 
-enum TestResult {
-    Passed5,
-    Passed7,
-    Failed,
+// Итог теста в наборе: отчет и ответы строятся по номеру теста, а не по порядку успешных прогонов
+enum TestOutcome {
+    Completed(Grade, RunResult),
+    Error(String),
+    /// Не запускался: набор прерван по Ctrl-C
+    Skipped,
 }
 
-fn run_simple_test(config: &TestsConfig) -> Result<(TestResult, Vec<usize>), ()> {
+// Шкала курса: 5 баллов за тур не длиннее `pass`, 7 -- строго короче `excellent`
+// (как в исходных тестах: `score < score_max`), поэтому граница сдвинута на одно f64 вниз
+fn course_rubric(pass: f64, excellent: f64) -> Rubric {
+    Rubric::new().band("pass", 5, Threshold::Length(pass)).band(
+        "excellent",
+        7,
+        Threshold::Length(excellent.next_down()),
+    )
+}
+
+fn run_solver_test(
+    config: &TestsConfig,
+    make_solver: impl FnOnce(TspData) -> Box<dyn TspSolver>,
+) -> Result<(Grade, RunResult), String> {
     let filename = config.filename;
+    // Все события прогона (включая события солвера) помечаются файлом и алгоритмом
+    let span = info_span!("instance", file = filename, solver = tracing::field::Empty);
+    let _guard = span.enter();
 
     if !Path::new(filename).exists() {
-        eprintln!("Error: File '{}' not found.", filename);
-        eprintln!(
-            "Please make sure you have a 'data' folder next to Cargo.toml containing the test file."
+        error!(
+            "File not found. Please make sure you have a 'data' folder next to Cargo.toml containing the test file."
         );
-        return Err(());
+        return Err(format!("file {} not found", filename));
     }
 
     match TspData::new(filename) {
+        Ok(data) if data.n == 0 => {
+            error!("instance has no cities");
+            Err(format!("{} has no cities", filename))
+        }
         Ok(data) => {
-            println!("Loaded {} cities.", data.n);
-
-            let num_particles = 128;
-            let iterations = 512;
-            let w = 0.7;
-            let c1 = 1.5;
-            let c2 = 1.5;
+            info!(cities = data.n, "loaded instance");
 
-            let mut solver = PsoTspSolver::new(data, num_particles, iterations, w, c1, c2);
+            // Солвер забирает данные себе, копия координат -- для кадров и пересчета длины
+            let instance = data.clone();
+            let mut solver = make_solver(data);
+            span.record("solver", solver.name());
+            solver.set_cancel_token(CANCEL.clone());
+            let metrics = MetricsObserver::new();
+            let run_metrics = metrics.metrics();
+            let mut observers: Vec<Box<dyn SolverObserver>> =
+                vec![Box::new(LogObserver::default()), Box::new(metrics)];
+            let history = HISTORY_DIR.get().map(|_| {
+                let recorder = HistoryRecorder::new();
+                let history = recorder.history();
+                observers.push(Box::new(recorder));
+                history
+            });
+            let frames = FRAMES_DIR.get().map(|_| {
+                let recorder = FrameRecorder::new(1);
+                let frames = recorder.frames();
+                observers.push(Box::new(recorder));
+                frames
+            });
+            solver.set_observer(Box::new(observers));
 
             solver.run();
 
-            println!("Final PSO Best Length: {:.2}", solver.gbest_score);
-
-            if solver.gbest_score <= config.score_min && solver.gbest_score >= config.score_max {
-                println!("{}", "5 points test passed!".yellow());
-                Ok((TestResult::Passed5, solver.gbest_tour))
-            } else if solver.gbest_score >= config.score_min {
-                println!("{}", "0 points test failed!".red());
-                Ok((TestResult::Failed, solver.gbest_tour))
-            } else {
-                println!("{}", "7 points test passed!".green());
-                Ok((TestResult::Passed7, solver.gbest_tour))
+            let stem = Path::new(filename).file_name().unwrap().to_string_lossy();
+            let base = format!("{}_{}", stem, solver.name().replace(' ', "_"));
+            if let (Some(dir), Some(frames)) = (FRAMES_DIR.get(), frames) {
+                let dir = dir.join(&base);
+                let config = RenderConfig {
+                    title: Some(format!("{} / {}", stem, solver.name())),
+                    ..RenderConfig::default()
+                };
+                match render_frames(&instance, &config, &frames.lock().unwrap(), &dir, "svg") {
+                    Ok(count) => info!(path = %dir.display(), count, "animation frames saved"),
+                    Err(e) => warn!(path = %dir.display(), error = %e, "cannot save frames"),
+                }
             }
+            if let (Some(dir), Some(history)) = (HISTORY_DIR.get(), history) {
+                let history = history.lock().unwrap();
+                for ext in ["csv", "json"] {
+                    let path = dir.join(format!("{}.{}", base, ext));
+                    match history.save(&path) {
+                        Ok(()) => info!(path = %path.display(), "convergence history saved"),
+                        Err(e) => warn!(path = %path.display(), error = %e, "cannot save history"),
+                    }
+                }
+            }
+
+            // Оценка -- по длине, пересчитанной по туру, как при проверке `verify`
+            let tour = solver.best_tour().to_vec();
+            let length = instance.calculate_tour_length(&tour);
+            // Без заданного эталона отклонение считается от нижней оценки 1-tree
+            let rubric = match config.rubric.reference {
+                Some(_) => config.rubric.clone(),
+                None => config
+                    .rubric
+                    .clone()
+                    .with_reference(Reference::LowerBound(one_tree_bound(&instance))),
+            };
+            let grade = rubric.grade(length);
+            info!(
+                best = length,
+                band = grade.band.as_deref().unwrap_or("-"),
+                points = grade.points,
+                "final best length {:.2}",
+                length
+            );
+            let metrics = run_metrics.lock().unwrap();
+            Ok((
+                grade.clone(),
+                RunResult {
+                    test_id: 0,
+                    instance: filename.to_string(),
+                    algorithm: solver.name().to_string(),
+                    params: metrics.params.clone(),
+                    seed: solver.seed(),
+                    length,
+                    points: grade.points,
+                    elapsed: metrics.elapsed,
+                    tour,
+                },
+            ))
         }
         Err(e) => {
-            eprintln!("Error loading data: {}", e);
-            Err(())
+            error!(error = %e, "error loading data");
+            Err(format!("cannot load {}: {}", filename, e))
         }
     }
 }
 
-fn run_test(config: &TestsConfig) -> Result<(TestResult, Vec<usize>), ()> {
-    let filename = config.filename;
+fn run_simple_test(
+    config: &TestsConfig,
+    pso_config: PsoConfig,
+) -> Result<(Grade, RunResult), String> {
+    run_solver_test(config, |data| {
+        Box::new(PsoTspSolver::with_config(data, pso_config))
+    })
+}
 
-    if !Path::new(filename).exists() {
-        eprintln!("Error: File '{}' not found.", filename);
-        eprintln!(
-            "Please make sure you have a 'data' folder next to Cargo.toml containing the test file."
-        );
-        return Err(());
+fn acs_config(config: &TestsConfig, update_rule: UpdateRule) -> AcsConfig {
+    // AS-правила используют чистый вероятностный выбор (q0 = 0)
+    let q0 = match update_rule {
+        UpdateRule::Acs => config.solver_configs.q0,
+        _ => 0.0,
+    };
+    AcsConfig {
+        n_ants: config.solver_configs.n_ants,
+        n_iterations: config.solver_configs.n_iterations,
+        q0,
+        beta: config.solver_configs.beta,
+        update_rule,
+        ..AcsConfig::default()
     }
+}
 
-    match TspData::new(filename) {
-        Ok(data) => {
-            println!("Loaded {} cities from {}", data.n, filename);
-
-            let mut solver = AcsTspSolver::new(
-                data.clone(),
-                config.solver_configs.n_ants,
-                config.solver_configs.n_iterations,
-                config.solver_configs.q0,
-                config.solver_configs.beta,
+fn mmas_config(config: &TestsConfig) -> MmasConfig {
+    MmasConfig {
+        n_ants: config.solver_configs.n_ants,
+        n_iterations: config.solver_configs.n_iterations,
+        beta: config.solver_configs.beta,
+        ..MmasConfig::default()
+    }
+}
+
+// Discrete PSO строит разность туров за O(n^2) на частицу и итерацию: экземпляры
+// больше этого размера пропускаются, остальные ограничены по времени
+const DPSO_MAX_CITIES: usize = 2000;
+
+fn dpso_config(config: &TestsConfig) -> DiscretePsoConfig {
+    DiscretePsoConfig {
+        num_particles: config.solver_configs.n_ants,
+        iterations: config.solver_configs.n_iterations,
+        time_limit: Some(Duration::from_secs(60)),
+        ..DiscretePsoConfig::default()
+    }
+}
+
+fn run_dpso_test(config: &TestsConfig) -> Result<(Grade, RunResult), String> {
+    if let Some(n) = city_count(Path::new(config.filename))
+        && n > DPSO_MAX_CITIES
+    {
+        return Err(format!(
+            "{} cities, Discrete PSO is limited to {}",
+            n, DPSO_MAX_CITIES
+        ));
+    }
+    let dpso_config = dpso_config(config);
+    run_solver_test(config, |data| {
+        Box::new(DiscretePsoTspSolver::new(data, dpso_config))
+    })
+}
+
+// Светлячки, как и Discrete PSO, строят разность туров за O(n^2), но для каждой пары
+// светлячков: большие экземпляры пропускаются, остальные ограничены по времени
+const FIREFLY_MAX_CITIES: usize = 2000;
+
+fn firefly_config() -> FireflyConfig {
+    FireflyConfig {
+        time_limit: Some(Duration::from_secs(60)),
+        ..FireflyConfig::default()
+    }
+}
+
+fn run_firefly_test(config: &TestsConfig) -> Result<(Grade, RunResult), String> {
+    if let Some(n) = city_count(Path::new(config.filename))
+        && n > FIREFLY_MAX_CITIES
+    {
+        return Err(format!(
+            "{} cities, Firefly is limited to {}",
+            n, FIREFLY_MAX_CITIES
+        ));
+    }
+    run_solver_test(config, |data| {
+        Box::new(FireflyTspSolver::new(data, firefly_config()))
+    })
+}
+
+fn run_test(config: &TestsConfig, update_rule: UpdateRule) -> Result<(Grade, RunResult), String> {
+    let acs_config = acs_config(config, update_rule);
+    run_solver_test(config, |data| {
+        Box::new(AcsTspSolver::with_config(data, acs_config))
+    })
+}
+
+fn run_mmas_test(config: &TestsConfig) -> Result<(Grade, RunResult), String> {
+    let mmas_config = mmas_config(config);
+    run_solver_test(config, |data| {
+        Box::new(MmasTspSolver::new(data, mmas_config))
+    })
+}
+
+// Алгоритмы бенчмарка -- те же режимы, что и в командной строке
+const BENCH_ALGORITHMS: [&str; 12] = [
+    "acs", "elitist", "rank", "mmas", "pso", "hpso", "dpso", "ga", "sa", "ils", "abc", "firefly",
+];
+
+// Солвер для попытки бенчмарка; все алгоритмы списка учитывают зерно попытки
+fn bench_solver(name: &str, c: &TestsConfig, data: TspData, seed: u64) -> Box<dyn TspSolver> {
+    let acs = |rule| AcsConfig {
+        seed: Some(seed),
+        ..acs_config(c, rule)
+    };
+    let pso = |config| PsoConfig {
+        seed: Some(seed),
+        ..config
+    };
+    match name {
+        "acs" => Box::new(AcsTspSolver::with_config(data, acs(UpdateRule::Acs))),
+        "elitist" => Box::new(AcsTspSolver::with_config(
+            data,
+            acs(UpdateRule::Elitist {
+                weight: c.solver_configs.n_ants as f64,
+            }),
+        )),
+        "rank" => Box::new(AcsTspSolver::with_config(
+            data,
+            acs(UpdateRule::RankBased { w: 6 }),
+        )),
+        "mmas" => Box::new(MmasTspSolver::new(
+            data,
+            MmasConfig {
+                seed: Some(seed),
+                ..mmas_config(c)
+            },
+        )),
+        "pso" => Box::new(PsoTspSolver::with_config(data, pso(PsoConfig::default()))),
+        "hpso" => Box::new(PsoTspSolver::with_config(data, pso(PsoConfig::hybrid()))),
+        "dpso" => Box::new(DiscretePsoTspSolver::new(
+            data,
+            DiscretePsoConfig {
+                seed: Some(seed),
+                ..dpso_config(c)
+            },
+        )),
+        "ga" => Box::new(GaTspSolver::new(
+            data,
+            GaConfig {
+                seed: Some(seed),
+                ..GaConfig::default()
+            },
+        )),
+        "sa" => Box::new(SaTspSolver::new(
+            data,
+            SaConfig {
+                seed: Some(seed),
+                ..SaConfig::default()
+            },
+        )),
+        "ils" => {
+            // Бюджет ILS -- 1 мс на город (от 1 до 10 с), а не полные 10 с на каждую попытку
+            let time_limit = Duration::from_millis(data.n as u64)
+                .clamp(Duration::from_secs(1), IlsConfig::default().time_limit);
+            Box::new(IlsTspSolver::new(
+                data,
+                IlsConfig {
+                    time_limit,
+                    seed: Some(seed),
+                    ..IlsConfig::default()
+                },
+            ))
+        }
+        "abc" => Box::new(AbcTspSolver::new(
+            data,
+            AbcConfig {
+                seed: Some(seed),
+                ..AbcConfig::default()
+            },
+        )),
+        _ => Box::new(FireflyTspSolver::new(
+            data,
+            FireflyConfig {
+                seed: Some(seed),
+                ..firefly_config()
+            },
+        )),
+    }
+}
+
+// Число городов из первой строки файла экземпляра
+fn city_count(path: &Path) -> Option<usize> {
+    let file = File::open(path).ok()?;
+    let mut first = String::new();
+    io::BufRead::read_line(&mut io::BufReader::new(file), &mut first).ok()?;
+    first.trim().parse().ok()
+}
+
+// Все экземпляры `data/` по возрастанию размера: для файлов из `tests` -- их настройки,
+// для остальных -- параметры ближайшего по размеру теста (в логарифмической шкале) без шкалы оценки
+fn data_suite<'a>(tests: &[TestsConfig], paths: &'a [(usize, String)]) -> Vec<TestsConfig<'a>> {
+    paths
+        .iter()
+        .filter_map(|(n, path)| {
+            let known = tests
+                .iter()
+                .find(|t| Path::new(t.filename).file_name() == Path::new(path).file_name());
+            if let Some(test) = known {
+                return Some(TestsConfig {
+                    filename: path,
+                    rubric: test.rubric.clone(),
+                    solver_configs: test.solver_configs,
+                });
+            }
+            let distance = |t: &TestsConfig| {
+                let m = city_count(Path::new(t.filename)).unwrap_or(1).max(1);
+                ((*n).max(1) as f64 / m as f64).ln().abs()
+            };
+            let nearest = tests
+                .iter()
+                .min_by(|a, b| distance(a).total_cmp(&distance(b)))?;
+            Some(TestsConfig {
+                filename: path,
+                rubric: Rubric::new(),
+                solver_configs: nearest.solver_configs,
+            })
+        })
+        .collect()
+}
+
+fn data_files(dir: &Path) -> Vec<(usize, String)> {
+    let mut files: Vec<(usize, String)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter_map(|path| Some((city_count(&path)?, path.to_str()?.to_string())))
+        .collect();
+    files.sort();
+    files
+}
+
+// K попыток каждого алгоритма на каждом тесте с общими зернами; отчет -- таблица и bench/*.csv
+fn run_benchmark(
+    tests: &[TestsConfig],
+    trials: usize,
+    algorithms: &[&str],
+    max_cities: usize,
+    base_seed: u64,
+) {
+    let seeds = trial_seeds(base_seed, trials);
+    let mut report = BenchReport::default();
+    for config in tests {
+        if CANCEL.is_cancelled() {
+            break;
+        }
+        let data = match TspData::new(config.filename) {
+            Ok(data) if data.n > 0 => data,
+            Ok(_) => {
+                error!(file = config.filename, "instance has no cities");
+                continue;
+            }
+            Err(e) => {
+                error!(file = config.filename, error = %e, "error loading data");
+                continue;
+            }
+        };
+        if data.n > max_cities {
+            info!(
+                file = config.filename,
+                cities = data.n,
+                max_cities,
+                "instance skipped"
+            );
+            continue;
+        }
+        let instance = Path::new(config.filename)
+            .file_name()
+            .map_or_else(String::new, |f| f.to_string_lossy().into_owned());
+        for &algorithm in algorithms {
+            let _span = info_span!("bench", instance, algorithm).entered();
+            let limit = match algorithm {
+                "dpso" => DPSO_MAX_CITIES,
+                "firefly" => FIREFLY_MAX_CITIES,
+                _ => usize::MAX,
+            };
+            if data.n > limit {
+                info!(cities = data.n, limit, "instance skipped");
+                continue;
+            }
+            let case = BenchCase::run(
+                &instance,
+                algorithm,
+                &data,
+                &config.rubric,
+                &seeds,
+                &CANCEL,
+                |data, seed| bench_solver(algorithm, config, data, seed),
             );
+            if let Some(stats) = case.length_stats() {
+                info!(
+                    trials = case.trials.len(),
+                    mean = stats.mean,
+                    std = stats.std,
+                    seeded = case.seeded,
+                    "trials finished"
+                );
+            }
+            report.cases.push(case);
+            if CANCEL.is_cancelled() {
+                warn!("interrupted, remaining trials skipped");
+                break;
+            }
+        }
+    }
 
-            solver.run();
+    println!("{}", report);
+    let dir = Path::new("./bench");
+    match report.save(dir) {
+        Ok(()) => info!(path = %dir.display(), "benchmark saved"),
+        Err(e) => error!(path = %dir.display(), error = %e, "cannot save benchmark"),
+    }
+}
+
+// Класс размера для настройки параметров: обучающие экземпляры -- файлы `data/`
+// до `max_cities` городов, кроме тестов; усилие прогона задано числом построенных туров
+struct SizeClass {
+    name: &'static str,
+    max_cities: usize,
+    /// Муравьи × итерации (ACS)
+    acs_tours: usize,
+    /// Частицы × итерации (PSO)
+    pso_tours: usize,
+}
+
+const SIZE_CLASSES: [SizeClass; 3] = [
+    SizeClass {
+        name: "small",
+        max_cities: 250,
+        acs_tours: 2048,
+        pso_tours: 32_768,
+    },
+    SizeClass {
+        name: "medium",
+        max_cities: 1000,
+        acs_tours: 512,
+        pso_tours: 16_384,
+    },
+    SizeClass {
+        name: "large",
+        max_cities: 5000,
+        acs_tours: 256,
+        pso_tours: 8192,
+    },
+];
+
+// Экземпляры меньше этого размера не учат ничему полезному
+const MIN_TRAINING_CITIES: usize = 20;
+
+fn tuning_space(algorithm: &str) -> Option<ParamSpace> {
+    let pso = ParamSpace::new().int("particles", 16, 256);
+    match algorithm {
+        "acs" => Some(
+            ParamSpace::new()
+                .int("n_ants", 4, 256)
+                .real("q0", 0.5, 0.99)
+                .real("beta", 1.0, 5.0)
+                .real("rho", 0.02, 0.5),
+        ),
+        "pso" => Some(
+            pso.real("inertia", 0.3, 0.95)
+                .real("c1", 0.5, 2.5)
+                .real("c2", 0.5, 2.5),
+        ),
+        "hpso" => Some(
+            pso.real("c1", 0.5, 2.5)
+                .real("c2", 0.5, 2.5)
+                .real("v_max", 0.02, 0.5),
+        ),
+        _ => None,
+    }
+}
 
+// Стартовые конфигурации гонки: ручные настройки тестов этого класса (ACS) или умолчания (PSO)
+fn initial_configs(
+    algorithm: &str,
+    tests: &[TestsConfig],
+    cities: &RangeInclusive<usize>,
+) -> Vec<Vec<f64>> {
+    let rho = AcsConfig::default().rho;
+    let mut configs: Vec<Vec<f64>> = match algorithm {
+        "acs" => tests
+            .iter()
+            .filter(|t| city_count(Path::new(t.filename)).is_some_and(|n| cities.contains(&n)))
+            .map(|t| {
+                let c = t.solver_configs;
+                vec![c.n_ants.clamp(4, 256) as f64, c.q0, c.beta, rho]
+            })
+            .collect(),
+        "pso" => {
+            let c = PsoConfig::default();
+            let inertia = match c.inertia {
+                InertiaSchedule::Constant(w) => w,
+                InertiaSchedule::LinearDecreasing { start, end } => (start + end) / 2.0,
+            };
+            vec![vec![c.num_particles as f64, inertia, c.c1, c.c2]]
+        }
+        _ => {
+            let c = PsoConfig::hybrid();
+            vec![vec![
+                c.num_particles as f64,
+                c.c1,
+                c.c2,
+                c.v_max.unwrap_or(0.1),
+            ]]
+        }
+    };
+    configs.dedup();
+    configs
+}
+
+fn tuned_solver(
+    algorithm: &str,
+    space: &ParamSpace,
+    class: &SizeClass,
+    values: &[f64],
+    data: TspData,
+    seed: u64,
+) -> Box<dyn TspSolver> {
+    let get = |name| space.get(values, name);
+    match algorithm {
+        "acs" => {
+            let n_ants = get("n_ants") as usize;
+            Box::new(AcsTspSolver::with_config(
+                data,
+                AcsConfig {
+                    n_ants,
+                    n_iterations: (class.acs_tours / n_ants).max(1),
+                    q0: get("q0"),
+                    beta: get("beta"),
+                    rho: get("rho"),
+                    seed: Some(seed),
+                    ..AcsConfig::default()
+                },
+            ))
+        }
+        "pso" => {
+            let particles = get("particles") as usize;
+            Box::new(PsoTspSolver::with_config(
+                data,
+                PsoConfig {
+                    num_particles: particles,
+                    iterations: (class.pso_tours / particles).max(1),
+                    inertia: InertiaSchedule::Constant(get("inertia")),
+                    c1: get("c1"),
+                    c2: get("c2"),
+                    seed: Some(seed),
+                    ..PsoConfig::default()
+                },
+            ))
+        }
+        _ => {
+            let particles = get("particles") as usize;
+            Box::new(PsoTspSolver::with_config(
+                data,
+                PsoConfig {
+                    num_particles: particles,
+                    iterations: (class.pso_tours / particles).max(1),
+                    c1: get("c1"),
+                    c2: get("c2"),
+                    v_max: Some(get("v_max")),
+                    seed: Some(seed),
+                    ..PsoConfig::hybrid()
+                },
+            ))
+        }
+    }
+}
+
+// Гонки по классам размера до `max_cities`; рекомендованные конфигурации -- в tuning/<алгоритм>.csv
+fn run_tuning(tests: &[TestsConfig], algorithm: &str, budget: usize, max_cities: usize, seed: u64) {
+    let Some(space) = tuning_space(algorithm) else {
+        error!(algorithm, "tuning supports acs, pso and hpso");
+        return;
+    };
+    let files = data_files(Path::new("./data"));
+    let is_test = |path: &str| {
+        tests
+            .iter()
+            .any(|t| Path::new(t.filename).file_name() == Path::new(path).file_name())
+    };
+
+    let mut recommended: Vec<(&SizeClass, RangeInclusive<usize>, usize, TuningResult)> = Vec::new();
+    let mut lower = MIN_TRAINING_CITIES;
+    for class in &SIZE_CLASSES {
+        // Класс обрезается по `max_cities`: обучение только на экземплярах не больше него
+        let cities = lower..=class.max_cities.min(max_cities);
+        lower = class.max_cities + 1;
+        if *cities.start() > max_cities || CANCEL.is_cancelled() {
+            break;
+        }
+        let training: Vec<TspData> = files
+            .iter()
+            .filter(|(n, path)| cities.contains(n) && !is_test(path))
+            .filter_map(|(_, path)| TspData::new(path).ok())
+            .collect();
+        if training.is_empty() {
+            warn!(class = class.name, "no training instances");
+            continue;
+        }
+
+        let _span = info_span!("tune", algorithm, class = class.name).entered();
+        info!(instances = training.len(), budget, "racing");
+        let initial = initial_configs(algorithm, tests, &cities);
+        let config = TunerConfig {
+            budget,
+            seed,
+            ..TunerConfig::default()
+        };
+        let result = tune(
+            &space,
+            &config,
+            &training,
+            &initial,
+            &CANCEL,
+            |values, data, seed| tuned_solver(algorithm, &space, class, values, data, seed),
+        );
+        println!(
+            "{} {} ({}..={} cities, {} instances)\n{}\n",
+            algorithm,
+            class.name,
+            cities.start(),
+            cities.end(),
+            training.len(),
+            result
+        );
+        recommended.push((class, cities, training.len(), result));
+    }
+
+    println!("Recommended {} configurations:", algorithm);
+    for (class, cities, _, result) in &recommended {
+        if let Some(best) = result.best() {
             println!(
-                "Final Best Tour Length: {:.2} / {:.2} {:.2}",
-                solver.best_score, config.score_min, config.score_max
+                "{:<8} {:>5}..={:<5} {}",
+                class.name,
+                cities.start(),
+                cities.end(),
+                space.describe(&best.candidate.values)
             );
+        }
+    }
 
-            if solver.best_score <= config.score_min && solver.best_score >= config.score_max {
-                println!("{}", "5 points test passed!".yellow());
-                Ok((TestResult::Passed5, solver.best_tour))
-            } else if solver.best_score >= config.score_min {
-                println!("{}", "0 points test failed!".red());
-                Ok((TestResult::Failed, solver.best_tour))
-            } else {
-                println!("{}", "7 points test passed!".green());
-                Ok((TestResult::Passed7, solver.best_tour))
+    let dir = Path::new("./tuning");
+    let path = dir.join(format!("{}.csv", algorithm));
+    let saved = fs::create_dir_all(dir)
+        .and_then(|()| File::create(&path))
+        .and_then(|file| {
+            let mut w = BufWriter::new(file);
+            writeln!(
+                w,
+                "class,min_cities,max_cities,instances,tours,runs,mean_cost,mean_rank,config"
+            )?;
+            for (class, cities, instances, result) in &recommended {
+                let Some(best) = result.best() else {
+                    continue;
+                };
+                let tours = if algorithm == "acs" {
+                    class.acs_tours
+                } else {
+                    class.pso_tours
+                };
+                writeln!(
+                    w,
+                    "{},{},{},{},{},{},{},{},{}",
+                    class.name,
+                    cities.start(),
+                    cities.end(),
+                    instances,
+                    tours,
+                    result.runs,
+                    best.mean_cost,
+                    best.mean_rank,
+                    space.describe(&best.candidate.values)
+                )?;
             }
+            w.flush()
+        });
+    match saved {
+        Ok(()) => info!(path = %path.display(), "tuning saved"),
+        Err(e) => error!(path = %path.display(), error = %e, "cannot save tuning"),
+    }
+}
+
+// Сравнение двух алгоритмов по попыткам, сохраненным режимом `bench`
+fn compare_algorithms(dir: &Path, a: &str, b: &str, alpha: f64) {
+    let report = match BenchReport::load(dir) {
+        Ok(report) => report,
+        Err(e) => {
+            error!(path = %dir.display(), error = %e, "cannot load benchmark, run `bench` first");
+            return;
         }
+    };
+    let comparison = ComparisonReport::new(&report, a, b, alpha);
+    if comparison.instances.is_empty() {
+        error!(a, b, path = %dir.display(), "no instances with trials of both algorithms");
+        return;
+    }
+    println!("{}", comparison);
+
+    let path = dir.join(format!("compare_{}_{}.csv", a, b));
+    let saved = File::create(&path).and_then(|file| {
+        let mut w = BufWriter::new(file);
+        comparison.write_csv(&mut w)?;
+        w.flush()
+    });
+    match saved {
+        Ok(()) => info!(path = %path.display(), "comparison saved"),
+        Err(e) => error!(path = %path.display(), error = %e, "cannot save comparison"),
+    }
+}
+
+// Туры из файла ответов по номеру теста; отсутствующие строки -- холодный старт
+fn load_warm_tours<'a>(tests: &[TestsConfig<'a>], filename: &str) -> HashMap<&'a str, Vec<usize>> {
+    tests
+        .iter()
+        .enumerate()
+        .filter_map(|(i, config)| match read_tour(filename, Some(i)) {
+            Ok(tour) => Some((config.filename, tour)),
+            Err(e) => {
+                warn!(file = config.filename, error = %e, "no warm start");
+                None
+            }
+        })
+        .collect()
+}
+
+// Прогон всех тестов набора; ошибка или паника солвера в одном тесте не останавливает остальные
+fn run_suite(
+    tests: &[TestsConfig],
+    label: &str,
+    runner: impl Fn(&TestsConfig) -> Result<(Grade, RunResult), String>,
+) -> Vec<TestOutcome> {
+    let _suite = info_span!("suite", label).entered();
+    let outcomes: Vec<TestOutcome> = tests
+        .iter()
+        .enumerate()
+        .map(|(i, config)| {
+            if CANCEL.is_cancelled() {
+                return TestOutcome::Skipped;
+            }
+            info!(test = i, file = config.filename, "running test");
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| runner(config))).unwrap_or_else(
+                |payload| {
+                    let message = payload
+                        .downcast_ref::<&str>()
+                        .map(|m| m.to_string())
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_default();
+                    Err(format!("solver panicked: {}", message))
+                },
+            );
+            match outcome {
+                Ok((result, mut run)) => {
+                    run.test_id = i;
+                    TestOutcome::Completed(result, run)
+                }
+                Err(e) => {
+                    error!(test = i, error = %e, "test could not be completed, continuing");
+                    TestOutcome::Error(e)
+                }
+            }
+        })
+        .collect();
+
+    let skipped = outcomes
+        .iter()
+        .filter(|o| matches!(o, TestOutcome::Skipped))
+        .count();
+    if skipped > 0 {
+        warn!(skipped, "interrupted, remaining tests skipped");
+    }
+    outcomes
+}
+
+// Строки итоговой таблицы для набора; у незавершенных тестов вместо оценки -- причина
+fn score_rows(tests: &[TestsConfig], label: &str, outcomes: &[TestOutcome]) -> Vec<ScoreRow> {
+    tests
+        .iter()
+        .zip(outcomes)
+        .enumerate()
+        .map(|(i, (config, outcome))| {
+            let instance = Path::new(config.filename)
+                .file_name()
+                .map_or_else(String::new, |f| f.to_string_lossy().into_owned());
+            let (algorithm, length, grade, note) = match outcome {
+                TestOutcome::Completed(grade, run) => (
+                    run.algorithm.clone(),
+                    Some(run.length),
+                    Some(grade.clone()),
+                    String::new(),
+                ),
+                TestOutcome::Error(e) => (label.to_string(), None, None, format!("error: {}", e)),
+                TestOutcome::Skipped => (label.to_string(), None, None, "skipped".to_string()),
+            };
+            ScoreRow {
+                test_id: i,
+                instance,
+                algorithm,
+                length,
+                grade,
+                max_points: config.rubric.max_points(),
+                note,
+            }
+        })
+        .collect()
+}
+
+// Ответы завершенных тестов: answers/*.txt для ноутбука и JSON рядом
+fn write_answers(outcomes: &[TestOutcome], answer_filename: &str) {
+    let mut answer = String::new();
+    let mut results = ResultSet::default();
+    for outcome in outcomes {
+        if let TestOutcome::Completed(_, run) = outcome {
+            answer.push_str(&format!("{} {} {:?}\n", run.test_id, run.points, run.tour));
+            results.results.push(run.clone());
+        }
+    }
+    if let Err(e) = fs::write(answer_filename, answer) {
+        error!(path = answer_filename, error = %e, "cannot write answers");
+    }
+    save_results(&results, answer_filename);
+}
+
+fn run_variant_suite(
+    tests: &[TestsConfig],
+    label: &str,
+    answer_filename: &str,
+    runner: impl Fn(&TestsConfig) -> Result<(Grade, RunResult), String>,
+) {
+    let outcomes = run_suite(tests, label, runner);
+    let table = ScoreTable {
+        rows: score_rows(tests, label, &outcomes),
+    };
+    println!("{}", table);
+    write_answers(&outcomes, answer_filename);
+}
+
+// Рядом с текстовым ответом -- тот же результат в JSON (экземпляр, алгоритм, параметры, длина, время)
+fn save_results(results: &ResultSet, answer_filename: &str) {
+    let path = Path::new(answer_filename).with_extension("json");
+    if let Err(e) = results.save(&path) {
+        warn!(path = %path.display(), error = %e, "cannot save results");
+    }
+}
+
+// Проверка одного тура: перестановка городов экземпляра и пересчитанная длина
+fn verify_tour(instance: &str, tour_file: &str, test_id: Option<usize>) -> bool {
+    let data = match TspData::new(instance) {
+        Ok(data) => data,
+        Err(e) => {
+            error!(instance, error = %e, "error loading data");
+            return false;
+        }
+    };
+    let tour = match read_tour(tour_file, test_id) {
+        Ok(tour) => tour,
         Err(e) => {
-            eprintln!("Error loading data: {}", e);
-            Err(())
+            error!(file = tour_file, error = %e, "cannot read tour");
+            return false;
+        }
+    };
+    match data.validate_tour(&tour) {
+        Ok(length) => {
+            info!(instance, cities = data.n, length, "valid tour");
+            true
+        }
+        Err(e) => {
+            error!(instance, error = %e, "invalid tour");
+            false
         }
     }
 }
 
+// Все строки answers/*.txt: тур по data/ из списка тестов, длина и заявленные баллы
+fn verify_answers(tests: &[TestsConfig], dir: &Path) -> bool {
+    let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+            .collect(),
+        Err(e) => {
+            error!(dir = %dir.display(), error = %e, "cannot read answers");
+            return false;
+        }
+    };
+    files.sort();
+
+    let mut instances: HashMap<usize, Result<TspData, String>> = HashMap::new();
+    let (mut checked, mut failed) = (0, 0);
+    for file in &files {
+        let file = file.to_string_lossy();
+        let answers = match read_answers(&file) {
+            Ok(answers) => answers,
+            Err(e) => {
+                error!(file = %file, error = %e, "cannot read answers");
+                failed += 1;
+                continue;
+            }
+        };
+        for answer in answers {
+            checked += 1;
+            let Some(config) = tests.get(answer.test_id) else {
+                error!(file = %file, test = answer.test_id, "unknown test");
+                failed += 1;
+                continue;
+            };
+            let data = match instances
+                .entry(answer.test_id)
+                .or_insert_with(|| TspData::new(config.filename).map_err(|e| e.to_string()))
+            {
+                Ok(data) => data,
+                Err(e) => {
+                    error!(instance = config.filename, error = %e, "error loading data");
+                    failed += 1;
+                    continue;
+                }
+            };
+            match data.validate_tour(&answer.tour) {
+                Ok(length) => {
+                    let points = config.rubric.grade(length).points;
+                    if points == answer.points {
+                        info!(file = %file, test = answer.test_id, length, points, "valid");
+                    } else {
+                        error!(
+                            file = %file,
+                            test = answer.test_id,
+                            length,
+                            claimed = answer.points,
+                            points,
+                            "points do not match length"
+                        );
+                        failed += 1;
+                    }
+                }
+                Err(e) => {
+                    error!(file = %file, test = answer.test_id, error = %e, "invalid tour");
+                    failed += 1;
+                }
+            }
+        }
+    }
+    info!(files = files.len(), checked, failed, "answers verified");
+    failed == 0
+}
+
+// Перечитывает файл результатов (JSON или CSV) и сверяет туры и длины с экземплярами
+fn check_results(path: &Path) {
+    let results = match ResultSet::load(path) {
+        Ok(results) => results,
+        Err(e) => {
+            error!(error = %e, "cannot load results");
+            return;
+        }
+    };
+    let errors = results.validate();
+    for (k, run) in results.results.iter().enumerate() {
+        match errors.iter().find(|(i, _)| *i == k) {
+            None => info!(
+                test = run.test_id,
+                instance = run.instance,
+                algorithm = run.algorithm,
+                length = run.length,
+                points = run.points,
+                "valid"
+            ),
+            Some((_, e)) => error!(
+                test = run.test_id,
+                instance = run.instance,
+                error = e,
+                "invalid"
+            ),
+        }
+    }
+    info!(
+        total = results.results.len(),
+        invalid = errors.len(),
+        "results checked"
+    );
+}
+
+// Картинки visualization/test<i>.svg/.png: лучший тур поверх тура для сравнения, как в visualizer.ipynb
+fn render_answers(tests: &[TestsConfig], best_filename: &str, other_filename: &str) {
+    let label = |f: &str| {
+        Path::new(f)
+            .file_stem()
+            .map(|s| s.to_string_lossy().trim_end_matches("_answer").to_string())
+            .unwrap_or_default()
+    };
+    let (best_label, other_label) = (label(best_filename), label(other_filename));
+    let _ = fs::create_dir_all("./visualization");
+
+    for (i, config) in tests.iter().enumerate() {
+        let data = match TspData::new(config.filename) {
+            Ok(data) => data,
+            Err(e) => {
+                error!(test = i, file = config.filename, error = %e, "error loading data");
+                continue;
+            }
+        };
+        let best = match read_tour(best_filename, Some(i)) {
+            Ok(tour) => tour,
+            Err(e) => {
+                warn!(test = i, error = %e, "no tour to render");
+                continue;
+            }
+        };
+        let other = read_tour(other_filename, Some(i)).ok();
+
+        let render_config = RenderConfig {
+            title: Some(format!("Test {}: {}", i, config.filename)),
+            ..RenderConfig::default()
+        };
+        let plot = match &other {
+            Some(other) => TourPlot::compare(
+                &data,
+                render_config,
+                (&best, &best_label),
+                (other, &other_label),
+            ),
+            None => {
+                let mut plot = TourPlot::new(&data, render_config);
+                plot.add_tour(&best, &best_label, Stroke::best());
+                plot
+            }
+        };
+        for ext in ["svg", "png"] {
+            let path = PathBuf::from(format!("./visualization/test{}.{}", i, ext));
+            match plot.save(&path) {
+                Ok(()) => info!(path = %path.display(), "tour rendered"),
+                Err(e) => warn!(path = %path.display(), error = %e, "cannot render tour"),
+            }
+        }
+    }
+}
+
+// Консоль (уровень из RUST_LOG, по умолчанию info) + журнал JSON lines в logs/run_log.jsonl
+fn init_logging() {
+    let filter = || EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    // Цвета только в терминале, чтобы перенаправленный вывод не засорялся escape-кодами
+    let console = fmt::layer()
+        .with_target(false)
+        .with_ansi(io::stdout().is_terminal())
+        .with_filter(filter());
+
+    let _ = fs::create_dir_all("./logs");
+    let json = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open("./logs/run_log.jsonl")
+        .ok()
+        .map(|file| {
+            fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_writer(Mutex::new(file))
+                .with_filter(filter())
+        });
+
+    tracing_subscriber::registry()
+        .with(console)
+        .with(json)
+        .init();
+}
+
 fn main() {
+    init_logging();
+
+    // Первый Ctrl-C -- мягкая остановка с записью ответов, второй -- немедленный выход
+    let _ = ctrlc::set_handler(|| {
+        if CANCEL.is_cancelled() {
+            std::process::exit(130);
+        }
+        warn!("Ctrl-C: finishing with the best tour so far");
+        CANCEL.cancel();
+    });
+
     let tests: Vec<TestsConfig> = vec![
         TestsConfig {
             filename: "./data/tsp_51_1",
-            score_min: 482.0,
-            score_max: 430.0,
+            rubric: course_rubric(482.0, 430.0),
             solver_configs: SolverConfig {
                 n_ants: 32,
                 n_iterations: 128,
@@ -134,8 +1211,7 @@ fn main() {
         },
         TestsConfig {
             filename: "./data/tsp_100_3",
-            score_min: 23_433.0,
-            score_max: 20_800.0,
+            rubric: course_rubric(23_433.0, 20_800.0),
             solver_configs: SolverConfig {
                 n_ants: 32,
                 n_iterations: 256,
@@ -145,8 +1221,7 @@ fn main() {
         },
         TestsConfig {
             filename: "./data/tsp_200_2",
-            score_min: 35_985.0,
-            score_max: 30_000.0,
+            rubric: course_rubric(35_985.0, 30_000.0),
             solver_configs: SolverConfig {
                 n_ants: 32,
                 n_iterations: 256,
@@ -156,8 +1231,7 @@ fn main() {
         },
         TestsConfig {
             filename: "./data/tsp_574_1",
-            score_min: 40_000.0,
-            score_max: 37_600.0,
+            rubric: course_rubric(40_000.0, 37_600.0),
             solver_configs: SolverConfig {
                 n_ants: 128,
                 n_iterations: 2048,
@@ -167,8 +1241,7 @@ fn main() {
         },
         TestsConfig {
             filename: "./data/tsp_1889_1",
-            score_min: 378_069.0,
-            score_max: 323_000.0,
+            rubric: course_rubric(378_069.0, 323_000.0),
             solver_configs: SolverConfig {
                 n_ants: 256,
                 n_iterations: 8,
@@ -178,85 +1251,376 @@ fn main() {
         },
         TestsConfig {
             filename: "./data/tsp_33810_1",
-            score_min: 78_478_868.0,
-            score_max: 67_700_000.0,
+            rubric: course_rubric(78_478_868.0, 67_700_000.0),
             solver_configs: SolverConfig {
                 n_ants: 32,
-                n_iterations: 1 * 0,
+                n_iterations: 0,
                 q0: 0.9,
                 beta: 2.0,
             },
         },
     ];
-    let mut simple_results = Vec::new();
-    let mut simple_answer = String::new();
-
-    let mut results = Vec::new();
-    let mut answer = String::new();
-
-    let simple_answer_filename = "./answers/classic_pso_answer.txt";
-    let answer_filename = "./answers/improved_acs_answer.txt";
 
-    // Running simple tests
-    for config in tests.iter() {
-        println!(
-            "{1} {}",
-            "Running simple test on file:".white().bold(),
-            config.filename
-        );
-        match run_simple_test(config) {
-            Ok(result) => simple_results.push(result),
-            Err(_) => println!("{}", "Test could not be completed due to an error.".red()),
+    // Альтернативные режимы: `cargo run --release -- <mmas|elitist|rank|dpso|hpso|ga|sa|ils|abc|firefly|render|results|verify|bench|compare|tune>`
+    // Флаги убираются из аргументов, дальше позиционные: <режим> [параметр]
+    let mut args: Vec<String> = env::args().collect();
+    for (flag, default, target) in [
+        ("--history", "./history", &HISTORY_DIR),
+        ("--frames", "./frames", &FRAMES_DIR),
+    ] {
+        // `--flag`, `--flag <dir>` или `--flag=<dir>`; следующий аргумент считается каталогом,
+        // если это не другой флаг и не имя режима
+        if let Some(k) = args
+            .iter()
+            .position(|a| a == flag || a.starts_with(&format!("{}=", flag)))
+        {
+            let arg = args.remove(k);
+            let dir = match arg.split_once('=') {
+                Some((_, dir)) => PathBuf::from(dir),
+                None if args
+                    .get(k)
+                    .is_some_and(|a| !a.starts_with("--") && !MODES.contains(&a.as_str())) =>
+                {
+                    PathBuf::from(args.remove(k))
+                }
+                None => PathBuf::from(default),
+            };
+            let _ = fs::create_dir_all(&dir);
+            let _ = target.set(dir);
         }
-        println!("----------------------------------------");
     }
 
-    // Running tests
-    for config in tests.iter() {
-        println!(
-            "{1} {}",
-            "Running test on file:".white().bold(),
-            config.filename
-        );
-        match run_test(config) {
-            Ok(result) => results.push(result),
-            Err(_) => println!("{}", "Test could not be completed due to an error.".red()),
+    // `--all`: бенчмарк по всем файлам `data/`, а не только по тестам
+    let all_data = match args.iter().position(|a| a == "--all") {
+        Some(k) => {
+            args.remove(k);
+            true
         }
-        println!("----------------------------------------");
-    }
+        None => false,
+    };
 
-    for i in 0..tests.len() {
-        match &simple_results[i] {
-            (TestResult::Passed5, tour) => {
-                println!("Test {i} PSO: {}", "5 points!".yellow());
-                simple_answer.push_str(&format!("{} {} {}\n", i, 5, format!("{:?}", tour)));
-            }
-            (TestResult::Passed7, tour) => {
-                println!("Test {i} PSO: {}", "7 points!".green());
-                simple_answer.push_str(&format!("{} {} {}\n", i, 7, format!("{:?}", tour)));
-            }
-            (TestResult::Failed, tour) => {
-                println!("Test {i} PSO: {}", "0 points!".red());
-                simple_answer.push_str(&format!("{} {} {}\n", i, 0, format!("{:?}", tour)));
-            }
+    match args.get(1).map(String::as_str) {
+        Some("mmas") => {
+            run_variant_suite(&tests, "MMAS", "./answers/mmas_answer.txt", run_mmas_test);
+            return;
         }
-
-        match &results[i] {
-            (TestResult::Passed5, tour) => {
-                println!("Test {i} ACS: {}", "5 points!".yellow());
-                answer.push_str(&format!("{} {} {}\n", i, 5, format!("{:?}", tour)));
+        Some("elitist") => {
+            run_variant_suite(
+                &tests,
+                "Elitist AS",
+                "./answers/elitist_as_answer.txt",
+                |c| {
+                    let weight = c.solver_configs.n_ants as f64;
+                    run_test(c, UpdateRule::Elitist { weight })
+                },
+            );
+            return;
+        }
+        Some("rank") => {
+            run_variant_suite(
+                &tests,
+                "Rank-based AS",
+                "./answers/rank_as_answer.txt",
+                |c| run_test(c, UpdateRule::RankBased { w: 6 }),
+            );
+            return;
+        }
+        Some("multistart") => {
+            run_variant_suite(
+                &tests,
+                "ACS (multi-start)",
+                "./answers/multistart_acs_answer.txt",
+                |c| {
+                    let acs_config = AcsConfig {
+                        n_ants: c.solver_configs.n_ants,
+                        n_iterations: c.solver_configs.n_iterations,
+                        q0: c.solver_configs.q0,
+                        beta: c.solver_configs.beta,
+                        multi_start: 64,
+                        ..AcsConfig::default()
+                    };
+                    run_solver_test(c, |data| {
+                        Box::new(AcsTspSolver::with_config(data, acs_config))
+                    })
+                },
+            );
+            return;
+        }
+        Some("checkpoint") => {
+            // Состояние пишется в ./checkpoints/<файл>.ckpt; повторный запуск продолжает с него
+            let _ = fs::create_dir_all("./checkpoints");
+            run_variant_suite(
+                &tests,
+                "ACS (checkpointed)",
+                "./answers/improved_acs_answer.txt",
+                |c| {
+                    let name = Path::new(c.filename).file_name().unwrap().to_string_lossy();
+                    let path = Path::new("./checkpoints").join(format!("{}.ckpt", name));
+                    let acs_config = AcsConfig {
+                        n_ants: c.solver_configs.n_ants,
+                        n_iterations: c.solver_configs.n_iterations,
+                        q0: c.solver_configs.q0,
+                        beta: c.solver_configs.beta,
+                        checkpoint: Some(Checkpointing {
+                            path: path.clone(),
+                            every: 16,
+                        }),
+                        ..AcsConfig::default()
+                    };
+                    let result = run_solver_test(c, |data| {
+                        if path.exists() {
+                            match AcsTspSolver::resume(data.clone(), acs_config.clone(), &path) {
+                                Ok(solver) => return Box::new(solver),
+                                Err(e) => warn!(error = %e, "cannot resume"),
+                            }
+                        }
+                        Box::new(AcsTspSolver::with_config(data, acs_config))
+                    });
+                    // Прогон завершен -- контрольная точка больше не нужна
+                    if !CANCEL.is_cancelled() {
+                        let _ = fs::remove_file(&path);
+                    }
+                    result
+                },
+            );
+            return;
+        }
+        Some("warm") => {
+            let source = args
+                .get(2)
+                .cloned()
+                .unwrap_or_else(|| "./answers/improved_acs_answer.txt".to_string());
+            let warm = load_warm_tours(&tests, &source);
+            run_variant_suite(
+                &tests,
+                "ACS (warm start)",
+                "./answers/warm_acs_answer.txt",
+                |c| {
+                    let acs_config = AcsConfig {
+                        n_ants: c.solver_configs.n_ants,
+                        n_iterations: c.solver_configs.n_iterations,
+                        q0: c.solver_configs.q0,
+                        beta: c.solver_configs.beta,
+                        ..AcsConfig::default()
+                    };
+                    run_solver_test(c, |data| {
+                        let mut solver = AcsTspSolver::with_config(data, acs_config);
+                        if let Some(tour) = warm.get(c.filename)
+                            && let Err(e) = solver.warm_start(tour)
+                        {
+                            warn!(error = %e, "warm start ignored");
+                        }
+                        Box::new(solver)
+                    })
+                },
+            );
+            return;
+        }
+        Some("warm-pso") => {
+            let source = args
+                .get(2)
+                .cloned()
+                .unwrap_or_else(|| "./answers/improved_acs_answer.txt".to_string());
+            let warm = load_warm_tours(&tests, &source);
+            run_variant_suite(
+                &tests,
+                "Hybrid PSO (warm start)",
+                "./answers/warm_pso_answer.txt",
+                |c| {
+                    run_solver_test(c, |data| {
+                        let mut solver = PsoTspSolver::with_config(data, PsoConfig::hybrid());
+                        if let Some(tour) = warm.get(c.filename)
+                            && let Err(e) = solver.warm_start(tour)
+                        {
+                            warn!(error = %e, "warm start ignored");
+                        }
+                        Box::new(solver)
+                    })
+                },
+            );
+            return;
+        }
+        Some("dpso") => {
+            run_variant_suite(
+                &tests,
+                "Discrete PSO",
+                "./answers/discrete_pso_answer.txt",
+                run_dpso_test,
+            );
+            return;
+        }
+        Some("hpso") => {
+            run_variant_suite(
+                &tests,
+                "Hybrid PSO",
+                "./answers/hybrid_pso_answer.txt",
+                |c| run_simple_test(c, PsoConfig::hybrid()),
+            );
+            return;
+        }
+        Some("ga") => {
+            run_variant_suite(&tests, "GA", "./answers/ga_answer.txt", |c| {
+                run_solver_test(c, |data| {
+                    Box::new(GaTspSolver::new(data, GaConfig::default()))
+                })
+            });
+            return;
+        }
+        Some("sa") => {
+            run_variant_suite(&tests, "SA", "./answers/sa_answer.txt", |c| {
+                run_solver_test(c, |data| {
+                    Box::new(SaTspSolver::new(data, SaConfig::default()))
+                })
+            });
+            return;
+        }
+        Some("ils") => {
+            run_variant_suite(&tests, "ILS", "./answers/ils_answer.txt", |c| {
+                run_solver_test(c, |data| {
+                    Box::new(IlsTspSolver::new(data, IlsConfig::default()))
+                })
+            });
+            return;
+        }
+        Some("abc") => {
+            run_variant_suite(&tests, "ABC", "./answers/abc_answer.txt", |c| {
+                run_solver_test(c, |data| {
+                    Box::new(AbcTspSolver::new(data, AbcConfig::default()))
+                })
+            });
+            return;
+        }
+        Some("firefly") => {
+            run_variant_suite(
+                &tests,
+                "Firefly",
+                "./answers/firefly_answer.txt",
+                run_firefly_test,
+            );
+            return;
+        }
+        Some("bench") => {
+            let trials = args.get(2).and_then(|k| k.parse().ok()).unwrap_or(10);
+            let algorithms: Vec<&str> = args
+                .get(3)
+                .map_or(vec!["acs", "pso"], |list| list.split(',').collect());
+            if let Some(unknown) = algorithms.iter().find(|a| !BENCH_ALGORITHMS.contains(a)) {
+                error!(algorithm = unknown, known = ?BENCH_ALGORITHMS, "unknown algorithm");
+                return;
             }
-            (TestResult::Passed7, tour) => {
-                println!("Test {i} ACS: {}", "7 points!".green());
-                answer.push_str(&format!("{} {} {}\n", i, 7, format!("{:?}", tour)));
+            let max_cities = args.get(4).and_then(|n| n.parse().ok()).unwrap_or(2000);
+            let base_seed = args.get(5).and_then(|s| s.parse().ok()).unwrap_or(1);
+            if all_data {
+                let files = data_files(Path::new("./data"));
+                let suite = data_suite(&tests, &files);
+                run_benchmark(&suite, trials, &algorithms, max_cities, base_seed);
+            } else {
+                run_benchmark(&tests, trials, &algorithms, max_cities, base_seed);
             }
-            (TestResult::Failed, tour) => {
-                println!("Test {i} ACS: {}", "0 points!".red());
-                answer.push_str(&format!("{} {} {}\n", i, 0, format!("{:?}", tour)));
+            return;
+        }
+        Some("compare") => {
+            let a = args.get(2).map_or("acs", String::as_str);
+            let b = args.get(3).map_or("pso", String::as_str);
+            let dir = args.get(4).map_or("./bench", String::as_str);
+            let alpha = args.get(5).and_then(|p| p.parse().ok()).unwrap_or(0.05);
+            compare_algorithms(Path::new(dir), a, b, alpha);
+            return;
+        }
+        Some("tune") => {
+            let algorithm = args.get(2).map_or("acs", String::as_str);
+            let budget = args.get(3).and_then(|b| b.parse().ok()).unwrap_or(200);
+            let max_cities = args.get(4).and_then(|n| n.parse().ok()).unwrap_or(1000);
+            let seed = args.get(5).and_then(|s| s.parse().ok()).unwrap_or(1);
+            run_tuning(&tests, algorithm, budget, max_cities, seed);
+            return;
+        }
+        Some("verify") => {
+            let ok = match (args.get(2), args.get(3)) {
+                (Some(instance), Some(tour_file)) => verify_tour(
+                    instance,
+                    tour_file,
+                    args.get(4).and_then(|id| id.parse().ok()),
+                ),
+                (Some(dir), None) => verify_answers(&tests, Path::new(dir)),
+                _ => verify_answers(&tests, Path::new("./answers")),
+            };
+            if !ok {
+                std::process::exit(1);
             }
+            return;
+        }
+        Some("results") => {
+            let path = args
+                .get(2)
+                .map_or("./answers/improved_acs_answer.json", String::as_str);
+            check_results(Path::new(path));
+            return;
+        }
+        Some("render") => {
+            render_answers(
+                &tests,
+                args.get(2)
+                    .map_or("./answers/improved_acs_answer.txt", String::as_str),
+                args.get(3)
+                    .map_or("./answers/classic_pso_answer.txt", String::as_str),
+            );
+            return;
+        }
+        Some("construct") => {
+            let method = match args.get(2).map(String::as_str) {
+                None | Some("sfc") => Construction::SpaceFillingCurve,
+                Some("nn") => Construction::NearestNeighbour,
+                Some("greedy") => Construction::GreedyEdge,
+                Some("nearest") => Construction::NearestInsertion,
+                Some("cheapest") => Construction::CheapestInsertion,
+                Some("farthest") => Construction::FarthestInsertion,
+                Some("christofides") => Construction::Christofides,
+                Some(other) => {
+                    error!(construction = other, "unknown construction");
+                    return;
+                }
+            };
+            run_variant_suite(
+                &tests,
+                &format!("{:?}", method),
+                "./answers/construct_answer.txt",
+                |c| {
+                    run_solver_test(c, |data| {
+                        Box::new(ConstructionTspSolver::new(
+                            data,
+                            ConstructionConfig {
+                                method,
+                                ..ConstructionConfig::default()
+                            },
+                        ))
+                    })
+                },
+            );
+            return;
         }
+        _ => {}
+    }
+
+    let simple_answer_filename = "./answers/classic_pso_answer.txt";
+    let answer_filename = "./answers/improved_acs_answer.txt";
+
+    let simple_results = run_suite(&tests, "PSO", |config| {
+        run_simple_test(config, PsoConfig::default())
+    });
+    let results = run_suite(&tests, "ACS", |config| run_test(config, UpdateRule::Acs));
+
+    // PSO и ACS по каждому тесту рядом
+    let mut table = ScoreTable::default();
+    for (simple, improved) in score_rows(&tests, "PSO", &simple_results)
+        .into_iter()
+        .zip(score_rows(&tests, "ACS", &results))
+    {
+        table.rows.push(simple);
+        table.rows.push(improved);
     }
+    println!("{}", table);
 
-    let _ = fs::write(answer_filename, answer);
-    let _ = fs::write(simple_answer_filename, simple_answer);
+    write_answers(&results, answer_filename);
+    write_answers(&simple_results, simple_answer_filename);
 }
@@ -0,0 +1,365 @@
+use crate::tsp_data::TspData;
+use crate::tsp_observers::{MetricsObserver, SolverObserver};
+use crate::tsp_scoring::Rubric;
+use crate::tsp_solvers::{CancelToken, TspSolver};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const TRIALS_HEADER: &str = "instance,algorithm,trial,seed,length,elapsed_s";
+
+/// Одна попытка: зерно, длина лучшего тура и время (построение солвера + run)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Trial {
+    pub seed: u64,
+    pub length: f64,
+    pub elapsed: Duration,
+}
+
+/// Описательная статистика выборки; `std` -- выборочное (n - 1)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub std: f64,
+}
+
+impl Stats {
+    pub fn of(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len();
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+        };
+        let std = if n > 1 {
+            (sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        Some(Stats {
+            min: sorted[0],
+            max: sorted[n - 1],
+            mean,
+            median,
+            std,
+        })
+    }
+}
+
+/// Зерна попыток: одинаковые для всех алгоритмов при одном `base`,
+/// поэтому результаты разных алгоритмов можно сравнивать попарно
+pub fn trial_seeds(base: u64, count: usize) -> Vec<u64> {
+    // SplitMix64: соседние зерна дают независимые потоки ChaCha
+    let mut state = base;
+    (0..count)
+        .map(|_| {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        })
+        .collect()
+}
+
+/// Серия попыток одного алгоритма с одной конфигурацией на одном экземпляре
+#[derive(Clone, Debug)]
+pub struct BenchCase {
+    pub instance: String,
+    /// Метка алгоритма в отчете (один солвер может участвовать с разными конфигурациями)
+    pub algorithm: String,
+    /// Строка параметров из `SolverObserver::on_start`
+    pub params: String,
+    /// Учитывает ли солвер зерно; без этого попытки независимы, но не воспроизводимы
+    pub seeded: bool,
+    pub rubric: Rubric,
+    pub trials: Vec<Trial>,
+}
+
+impl BenchCase {
+    /// Попытки с заданными зернами (обычно `trial_seeds`); солверы молчат,
+    /// по отмене серия обрывается (прерванная попытка не учитывается)
+    pub fn run(
+        instance: &str,
+        algorithm: &str,
+        data: &TspData,
+        rubric: &Rubric,
+        seeds: &[u64],
+        cancel: &CancelToken,
+        make_solver: impl Fn(TspData, u64) -> Box<dyn TspSolver>,
+    ) -> Self {
+        let mut case = BenchCase {
+            instance: instance.to_string(),
+            algorithm: algorithm.to_string(),
+            params: String::new(),
+            seeded: false,
+            rubric: rubric.clone(),
+            trials: Vec::with_capacity(seeds.len()),
+        };
+        for &seed in seeds {
+            if cancel.is_cancelled() {
+                break;
+            }
+            let start = Instant::now();
+            let mut solver = make_solver(data.clone(), seed);
+            let metrics = MetricsObserver::new();
+            let run_metrics = metrics.metrics();
+            let observers: Vec<Box<dyn SolverObserver>> = vec![Box::new(metrics)];
+            solver.set_observer(Box::new(observers));
+            solver.set_cancel_token(cancel.clone());
+            solver.run();
+            if cancel.is_cancelled() {
+                break;
+            }
+
+            case.params = run_metrics.lock().unwrap().params.clone();
+            case.seeded = solver.seed().is_some();
+            case.trials.push(Trial {
+                seed,
+                length: data.calculate_tour_length(solver.best_tour()),
+                elapsed: start.elapsed(),
+            });
+        }
+        case
+    }
+
+    pub fn lengths(&self) -> Vec<f64> {
+        self.trials.iter().map(|t| t.length).collect()
+    }
+
+    pub fn length_stats(&self) -> Option<Stats> {
+        Stats::of(&self.lengths())
+    }
+
+    /// Время в секундах
+    pub fn time_stats(&self) -> Option<Stats> {
+        let times: Vec<f64> = self
+            .trials
+            .iter()
+            .map(|t| t.elapsed.as_secs_f64())
+            .collect();
+        Stats::of(&times)
+    }
+
+    /// Доля попыток, получивших ненулевые баллы
+    pub fn success_rate(&self) -> f64 {
+        self.rate(|length| self.rubric.grade(length).points > 0)
+    }
+
+    /// Для каждой полосы шкалы -- доля попыток, уложившихся в ее границу
+    pub fn band_rates(&self) -> Vec<(String, f64)> {
+        self.rubric
+            .bands
+            .iter()
+            .map(|band| {
+                let limit = self.rubric.limit(band.threshold);
+                let rate = self.rate(|length| limit.is_some_and(|l| length <= l));
+                (band.label.clone(), rate)
+            })
+            .collect()
+    }
+
+    fn rate(&self, pred: impl Fn(f64) -> bool) -> f64 {
+        if self.trials.is_empty() {
+            return 0.0;
+        }
+        self.trials.iter().filter(|t| pred(t.length)).count() as f64 / self.trials.len() as f64
+    }
+}
+
+/// Результаты бенчмарка: сводная таблица и выгрузка попыток в CSV
+#[derive(Clone, Debug, Default)]
+pub struct BenchReport {
+    pub cases: Vec<BenchCase>,
+}
+
+impl BenchReport {
+    /// Все попытки построчно -- для последующего статистического сравнения
+    pub fn write_trials_csv(&self, w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "{}", TRIALS_HEADER)?;
+        for case in &self.cases {
+            for (k, t) in case.trials.iter().enumerate() {
+                writeln!(
+                    w,
+                    "{},{},{},{},{},{:.6}",
+                    case.instance,
+                    case.algorithm,
+                    k,
+                    t.seed,
+                    t.length,
+                    t.elapsed.as_secs_f64()
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write_summary_csv(&self, w: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            w,
+            "instance,algorithm,trials,seeded,min,mean,median,std,max,time_mean_s,time_median_s,success_rate"
+        )?;
+        for case in &self.cases {
+            let (Some(l), Some(t)) = (case.length_stats(), case.time_stats()) else {
+                continue;
+            };
+            writeln!(
+                w,
+                "{},{},{},{},{},{},{},{},{},{:.6},{:.6},{}",
+                case.instance,
+                case.algorithm,
+                case.trials.len(),
+                case.seeded,
+                l.min,
+                l.mean,
+                l.median,
+                l.std,
+                l.max,
+                t.mean,
+                t.median,
+                case.success_rate()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Попытки в `<dir>/trials.csv`, сводка в `<dir>/summary.csv`
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let mut w = BufWriter::new(File::create(dir.join("trials.csv"))?);
+        self.write_trials_csv(&mut w)?;
+        w.flush()?;
+        let mut w = BufWriter::new(File::create(dir.join("summary.csv"))?);
+        self.write_summary_csv(&mut w)?;
+        w.flush()
+    }
+
+    /// Чтение попыток, записанных `save`; признак `seeded` берется из `summary.csv`,
+    /// если он есть, шкала оценки не сохраняется
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let path = dir.join("trials.csv");
+        let invalid = |line: usize, what: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}:{}: {}", path.display(), line, what),
+            )
+        };
+        let content = std::fs::read_to_string(&path)?;
+        let mut lines = content.lines().enumerate();
+        match lines.next() {
+            Some((_, header)) if header.trim() == TRIALS_HEADER => {}
+            _ => return Err(invalid(1, "missing or unknown header")),
+        }
+
+        let mut report = BenchReport::default();
+        for (k, line) in lines.filter(|(_, l)| !l.trim().is_empty()) {
+            let f: Vec<&str> = line.split(',').collect();
+            if f.len() != 6 {
+                return Err(invalid(k + 1, "expected 6 fields"));
+            }
+            let trial = Trial {
+                seed: f[3].parse().map_err(|_| invalid(k + 1, "bad seed"))?,
+                length: f[4].parse().map_err(|_| invalid(k + 1, "bad length"))?,
+                elapsed: f[5]
+                    .parse()
+                    .ok()
+                    .and_then(|s| Duration::try_from_secs_f64(s).ok())
+                    .ok_or_else(|| invalid(k + 1, "bad elapsed_s"))?,
+            };
+            match report.case_mut(f[0], f[1]) {
+                Some(case) => case.trials.push(trial),
+                None => report.cases.push(BenchCase {
+                    instance: f[0].to_string(),
+                    algorithm: f[1].to_string(),
+                    params: String::new(),
+                    seeded: false,
+                    rubric: Rubric::default(),
+                    trials: vec![trial],
+                }),
+            }
+        }
+
+        if let Ok(summary) = std::fs::read_to_string(dir.join("summary.csv")) {
+            for line in summary.lines().skip(1) {
+                let f: Vec<&str> = line.split(',').collect();
+                if let [instance, algorithm, _, seeded, ..] = f[..]
+                    && let Some(case) = report.case_mut(instance, algorithm)
+                {
+                    case.seeded = seeded == "true";
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    pub fn case(&self, instance: &str, algorithm: &str) -> Option<&BenchCase> {
+        self.cases
+            .iter()
+            .find(|c| c.instance == instance && c.algorithm == algorithm)
+    }
+
+    fn case_mut(&mut self, instance: &str, algorithm: &str) -> Option<&mut BenchCase> {
+        self.cases
+            .iter_mut()
+            .find(|c| c.instance == instance && c.algorithm == algorithm)
+    }
+
+    /// Экземпляры в порядке появления
+    pub fn instances(&self) -> Vec<&str> {
+        let mut instances: Vec<&str> = Vec::new();
+        for case in &self.cases {
+            if !instances.contains(&case.instance.as_str()) {
+                instances.push(&case.instance);
+            }
+        }
+        instances
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<12} {:<14} {:>6} {:>14} {:>14} {:>14} {:>12} {:>9} {:>8}  bands",
+            "instance", "algorithm", "trials", "min", "mean", "median", "std", "time, s", "success"
+        )?;
+        for case in &self.cases {
+            let Some(l) = case.length_stats() else {
+                writeln!(f, "{:<12} {:<14} {:>6}", case.instance, case.algorithm, 0)?;
+                continue;
+            };
+            let t = case.time_stats().map_or(0.0, |t| t.mean);
+            let bands: Vec<String> = case
+                .band_rates()
+                .iter()
+                .map(|(label, rate)| format!("{} {:.0}%", label, rate * 100.0))
+                .collect();
+            writeln!(
+                f,
+                "{:<12} {:<14} {:>6} {:>14.2} {:>14.2} {:>14.2} {:>12.2} {:>9.2} {:>7.0}%  {}",
+                case.instance,
+                case.algorithm,
+                case.trials.len(),
+                l.min,
+                l.mean,
+                l.median,
+                l.std,
+                t,
+                case.success_rate() * 100.0,
+                bands.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,634 @@
+use crate::tsp_bench::{BenchCase, BenchReport};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Результат критерия: статистика и двусторонний p-value
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestResult {
+    /// W+ (сумма рангов положительных разностей) для Вилкоксона, U первой выборки для Манна -- Уитни
+    pub statistic: f64,
+    /// Математическое ожидание статистики при H0: статистика больше -- первая выборка длиннее
+    pub expected: f64,
+    pub p_value: f64,
+    /// Число пар с ненулевой разностью (Вилкоксон) или суммарный размер выборок (Манн -- Уитни)
+    pub n: usize,
+    /// Точное перестановочное распределение (с учетом связок) или нормальное приближение
+    pub exact: bool,
+}
+
+// До этого размера распределение статистики считается точно, дальше -- нормальное приближение
+const EXACT_LIMIT: usize = 60;
+
+// Относительный допуск, при котором длины считаются равными (как в `RunResult::validate`)
+const TIE_TOLERANCE: f64 = 1e-9;
+
+fn same(a: f64, b: f64) -> bool {
+    (a - b).abs() <= TIE_TOLERANCE * a.abs().max(b.abs())
+}
+
+/// Критерий знаковых рангов Вилкоксона для пар `(x[i], y[i])`; нулевые разности отбрасываются
+pub fn wilcoxon_signed_rank(x: &[f64], y: &[f64]) -> TestResult {
+    let diffs: Vec<f64> = x
+        .iter()
+        .zip(y)
+        .filter(|&(&a, &b)| !same(a, b))
+        .map(|(a, b)| a - b)
+        .collect();
+    let n = diffs.len();
+    let expected = (n * (n + 1)) as f64 / 4.0;
+    if n == 0 {
+        return TestResult {
+            statistic: 0.0,
+            expected,
+            p_value: 1.0,
+            n,
+            exact: true,
+        };
+    }
+
+    let abs: Vec<f64> = diffs.iter().map(|d| d.abs()).collect();
+    let (ranks, ties) = ranks(&snap_ties(&abs));
+    let w_plus: f64 = diffs
+        .iter()
+        .zip(&ranks)
+        .filter(|(d, _)| **d > 0.0)
+        .fold(0.0, |sum, (_, r)| sum + r);
+
+    let exact = n <= EXACT_LIMIT;
+    let p_value = if exact {
+        // При H0 каждый ранг входит в W+ с вероятностью 1/2; ранги удвоены,
+        // чтобы средние ранги связок стали целыми
+        let doubled = doubled(&ranks);
+        let total: usize = doubled.iter().sum();
+        let mut dist = vec![0.0; total + 1];
+        dist[0] = 1.0;
+        for &r in &doubled {
+            for s in (r..=total).rev() {
+                dist[s] = 0.5 * (dist[s] + dist[s - r]);
+            }
+            for p in &mut dist[..r] {
+                *p *= 0.5;
+            }
+        }
+        two_sided(&dist, (w_plus * 2.0).round() as usize)
+    } else {
+        let nf = n as f64;
+        let var = nf * (nf + 1.0) * (2.0 * nf + 1.0) / 24.0 - ties / 48.0;
+        normal_two_sided(w_plus - expected, var)
+    };
+    TestResult {
+        statistic: w_plus,
+        expected,
+        p_value,
+        n,
+        exact,
+    }
+}
+
+/// Критерий Манна -- Уитни для независимых выборок
+pub fn mann_whitney_u(x: &[f64], y: &[f64]) -> TestResult {
+    let (n1, n2) = (x.len(), y.len());
+    let expected = (n1 * n2) as f64 / 2.0;
+    if n1 == 0 || n2 == 0 {
+        return TestResult {
+            statistic: 0.0,
+            expected,
+            p_value: 1.0,
+            n: n1 + n2,
+            exact: true,
+        };
+    }
+
+    let pooled: Vec<f64> = x.iter().chain(y).copied().collect();
+    let (ranks, ties) = ranks(&snap_ties(&pooled));
+    let r1: f64 = ranks[..n1].iter().sum();
+    let u = r1 - (n1 * (n1 + 1)) as f64 / 2.0;
+
+    let n = n1 + n2;
+    let exact = n <= EXACT_LIMIT;
+    let p_value = if exact {
+        // Все C(n, n1) разбиений рангов равновероятны; count[k][s] -- число
+        // k-подмножеств с суммой удвоенных рангов s
+        let doubled = doubled(&ranks);
+        let total: usize = doubled.iter().sum();
+        let mut count = vec![vec![0.0; total + 1]; n1 + 1];
+        count[0][0] = 1.0;
+        for &r in &doubled {
+            for k in (1..=n1).rev() {
+                for s in (r..=total).rev() {
+                    count[k][s] += count[k - 1][s - r];
+                }
+            }
+        }
+        let all: f64 = count[n1].iter().sum();
+        let dist: Vec<f64> = count[n1].iter().map(|c| c / all).collect();
+        two_sided(&dist, (r1 * 2.0).round() as usize)
+    } else {
+        let nf = n as f64;
+        let var = (n1 * n2) as f64 / 12.0 * (nf + 1.0 - ties / (nf * (nf - 1.0)));
+        normal_two_sided(u - expected, var)
+    };
+    TestResult {
+        statistic: u,
+        expected,
+        p_value,
+        n,
+        exact,
+    }
+}
+
+/// Критерий Фридмана: `costs[c][b]` -- результат кандидата `c` в блоке `b`, ранги
+/// считаются внутри блока; блоки сверх самой короткой строки не учитываются
+pub fn friedman(costs: &[Vec<f64>]) -> TestResult {
+    let k = costs.len();
+    let b = costs.iter().map(Vec::len).min().unwrap_or(0);
+    let expected = k.saturating_sub(1) as f64;
+    if k < 2 || b == 0 {
+        return TestResult {
+            statistic: 0.0,
+            expected,
+            p_value: 1.0,
+            n: b,
+            exact: false,
+        };
+    }
+
+    let mut rank_sums = vec![0.0; k];
+    let mut rank_squares = 0.0;
+    for ranks in block_ranks(costs, b) {
+        for (sum, r) in rank_sums.iter_mut().zip(&ranks) {
+            *sum += r;
+            rank_squares += r * r;
+        }
+    }
+    // Статистика с поправкой на связки, при H0 -- хи-квадрат с k - 1 степенями свободы
+    let (kf, bf) = (k as f64, b as f64);
+    let centre = bf * (kf + 1.0) / 2.0;
+    let spread: f64 = rank_sums.iter().map(|r| (r - centre).powi(2)).sum();
+    let denom = rank_squares - bf * kf * (kf + 1.0).powi(2) / 4.0;
+    let (statistic, p_value) = if denom > 0.0 {
+        let q = (kf - 1.0) * spread / denom;
+        (q, chi2_sf(q, kf - 1.0))
+    } else {
+        (0.0, 1.0)
+    };
+    TestResult {
+        statistic,
+        expected,
+        p_value,
+        n: b,
+        exact: false,
+    }
+}
+
+/// Средний ранг каждого кандидата по блокам (1 -- лучший, меньший результат)
+pub fn mean_ranks(costs: &[Vec<f64>]) -> Vec<f64> {
+    let b = costs.iter().map(Vec::len).min().unwrap_or(0);
+    let mut sums = vec![0.0; costs.len()];
+    for ranks in block_ranks(costs, b) {
+        for (sum, r) in sums.iter_mut().zip(&ranks) {
+            *sum += r;
+        }
+    }
+    sums.iter().map(|s| s / b.max(1) as f64).collect()
+}
+
+fn block_ranks(costs: &[Vec<f64>], blocks: usize) -> impl Iterator<Item = Vec<f64>> + '_ {
+    (0..blocks).map(|j| {
+        let block: Vec<f64> = costs.iter().map(|c| c[j]).collect();
+        ranks(&snap_ties(&block)).0
+    })
+}
+
+// Значения, равные с допуском `TIE_TOLERANCE`, заменяются наименьшим из них -- становятся связками
+fn snap_ties(values: &[f64]) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    values
+        .iter()
+        .map(|&v| sorted.iter().copied().find(|&s| same(s, v)).unwrap_or(v))
+        .collect()
+}
+
+// Средние ранги (с 1) и поправка на связки: сумма t^3 - t по группам равных значений
+fn ranks(values: &[f64]) -> (Vec<f64>, f64) {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut ties = 0.0;
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && values[order[j + 1]] == values[order[i]] {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        for &k in &order[i..=j] {
+            ranks[k] = rank;
+        }
+        let t = (j - i + 1) as f64;
+        ties += t * t * t - t;
+        i = j + 1;
+    }
+    (ranks, ties)
+}
+
+fn doubled(ranks: &[f64]) -> Vec<usize> {
+    ranks.iter().map(|r| (r * 2.0).round() as usize).collect()
+}
+
+// Двусторонний p-value по распределению `dist` и наблюдаемому значению `k`
+fn two_sided(dist: &[f64], k: usize) -> f64 {
+    let lower: f64 = dist[..=k.min(dist.len() - 1)].iter().sum();
+    let upper: f64 = dist[k.min(dist.len())..].iter().sum();
+    (2.0 * lower.min(upper)).min(1.0)
+}
+
+// Нормальное приближение с поправкой на непрерывность
+fn normal_two_sided(shift: f64, var: f64) -> f64 {
+    if var <= 0.0 {
+        return 1.0;
+    }
+    let z = (shift.abs() - 0.5).max(0.0) / var.sqrt();
+    erfc(z / std::f64::consts::SQRT_2).min(1.0)
+}
+
+// Дополнительная функция ошибок (Чебышевская аппроксимация, относительная ошибка < 1.2e-7)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let r = t * poly.exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+// P(X > x) для хи-квадрат с `dof` степенями свободы -- регуляризованная
+// верхняя неполная гамма-функция Q(dof / 2, x / 2)
+fn chi2_sf(x: f64, dof: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let (a, x) = (dof / 2.0, x / 2.0);
+    let scale = (-x + a * x.ln() - ln_gamma(a)).exp();
+    let q = if x < a + 1.0 {
+        // Ряд для нижней функции P(a, x)
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut ap = a;
+        for _ in 0..1000 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        1.0 - sum * scale
+    } else {
+        // Непрерывная дробь для Q(a, x) (метод Лентца)
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        scale * h
+    };
+    q.clamp(0.0, 1.0)
+}
+
+// Логарифм гамма-функции, аппроксимация Ланцоша (g = 7)
+fn ln_gamma(x: f64) -> f64 {
+    const COEF: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEF
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold(COEF[0], |sum, (i, c)| sum + c / (x + i as f64));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// Исход сравнения на экземпляре с точки зрения первого алгоритма (меньшая длина -- лучше)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Tie,
+    Loss,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestKind {
+    /// Попытки спарены по зерну
+    Wilcoxon,
+    /// Независимые выборки (солвер не учитывает зерно или зерна не совпали)
+    MannWhitney,
+}
+
+impl fmt::Display for TestKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TestKind::Wilcoxon => write!(f, "wilcoxon"),
+            TestKind::MannWhitney => write!(f, "mann-whitney"),
+        }
+    }
+}
+
+/// Сравнение двух серий попыток на одном экземпляре
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub instance: String,
+    pub median_a: f64,
+    pub median_b: f64,
+    pub kind: TestKind,
+    pub result: TestResult,
+    pub outcome: Outcome,
+}
+
+impl Comparison {
+    /// Wilcoxon, если оба солвера учитывают зерно и есть попытки с общими зернами,
+    /// иначе Mann -- Whitney; `None`, если у одной из серий нет попыток
+    pub fn of(a: &BenchCase, b: &BenchCase, alpha: f64) -> Option<Self> {
+        let median_a = a.length_stats()?.median;
+        let median_b = b.length_stats()?.median;
+
+        let mut paired = (Vec::new(), Vec::new());
+        if a.seeded && b.seeded {
+            let by_seed: HashMap<u64, f64> = b.trials.iter().map(|t| (t.seed, t.length)).collect();
+            for t in &a.trials {
+                if let Some(&length) = by_seed.get(&t.seed) {
+                    paired.0.push(t.length);
+                    paired.1.push(length);
+                }
+            }
+        }
+        let (kind, result) = if paired.0.is_empty() {
+            (
+                TestKind::MannWhitney,
+                mann_whitney_u(&a.lengths(), &b.lengths()),
+            )
+        } else {
+            (
+                TestKind::Wilcoxon,
+                wilcoxon_signed_rank(&paired.0, &paired.1),
+            )
+        };
+
+        let outcome = if result.p_value >= alpha {
+            Outcome::Tie
+        } else if result.statistic < result.expected {
+            Outcome::Win
+        } else {
+            Outcome::Loss
+        };
+        Some(Comparison {
+            instance: a.instance.clone(),
+            median_a,
+            median_b,
+            kind,
+            result,
+            outcome,
+        })
+    }
+
+    /// Относительная разница медиан: -0.02 -- первый алгоритм короче на 2%
+    pub fn median_gap(&self) -> f64 {
+        self.median_a / self.median_b.max(f64::MIN_POSITIVE) - 1.0
+    }
+}
+
+/// Попарное сравнение двух алгоритмов по всем экземплярам бенчмарка
+#[derive(Clone, Debug)]
+pub struct ComparisonReport {
+    pub a: String,
+    pub b: String,
+    /// Уровень значимости для исхода на экземпляре
+    pub alpha: f64,
+    pub instances: Vec<Comparison>,
+    /// Вилкоксон по экземплярам: отношения медиан a / b против 1
+    pub overall: Option<TestResult>,
+}
+
+impl ComparisonReport {
+    pub fn new(report: &BenchReport, a: &str, b: &str, alpha: f64) -> Self {
+        let instances: Vec<Comparison> = report
+            .instances()
+            .into_iter()
+            .filter_map(|instance| {
+                Comparison::of(report.case(instance, a)?, report.case(instance, b)?, alpha)
+            })
+            .collect();
+        let overall = (!instances.is_empty()).then(|| {
+            let ratios: Vec<f64> = instances.iter().map(|c| 1.0 + c.median_gap()).collect();
+            wilcoxon_signed_rank(&ratios, &vec![1.0; ratios.len()])
+        });
+        ComparisonReport {
+            a: a.to_string(),
+            b: b.to_string(),
+            alpha,
+            instances,
+            overall,
+        }
+    }
+
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.instances
+            .iter()
+            .filter(|c| c.outcome == outcome)
+            .count()
+    }
+
+    pub fn write_csv(&self, w: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            w,
+            "instance,a,b,median_a,median_b,test,n,statistic,p_value,exact,outcome"
+        )?;
+        for c in &self.instances {
+            writeln!(
+                w,
+                "{},{},{},{},{},{},{},{},{},{},{:?}",
+                c.instance,
+                self.a,
+                self.b,
+                c.median_a,
+                c.median_b,
+                c.kind,
+                c.result.n,
+                c.result.statistic,
+                c.result.p_value,
+                c.result.exact,
+                c.outcome
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ComparisonReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<12} {:<13} {:>4} {:>14} {:>14} {:>9} {:>10}  result",
+            "instance",
+            "test",
+            "n",
+            format!("median {}", self.a),
+            format!("median {}", self.b),
+            "diff",
+            "p-value"
+        )?;
+        for c in &self.instances {
+            writeln!(
+                f,
+                "{:<12} {:<13} {:>4} {:>14.2} {:>14.2} {:>+8.2}% {:>10.4}  {:?}",
+                c.instance,
+                c.kind.to_string(),
+                c.result.n,
+                c.median_a,
+                c.median_b,
+                c.median_gap() * 100.0,
+                c.result.p_value,
+                c.outcome
+            )?;
+        }
+        write!(
+            f,
+            "{} vs {}: win/tie/loss = {}/{}/{} (alpha = {})",
+            self.a,
+            self.b,
+            self.count(Outcome::Win),
+            self.count(Outcome::Tie),
+            self.count(Outcome::Loss),
+            self.alpha
+        )?;
+        if let Some(overall) = self.overall {
+            write!(
+                f,
+                "\nacross {} instances: Wilcoxon W+ = {}, p = {:.4}",
+                self.instances.len(),
+                overall.statistic,
+                overall.p_value
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, eps: f64) -> bool {
+        (a - b).abs() <= eps
+    }
+
+    #[test]
+    fn wilcoxon_exact_small_n() {
+        // Все 6 разностей положительны: W+ = 21, p = 2 / 2^6
+        let x = [11.0, 12.0, 13.0, 14.0, 15.0, 16.0];
+        let y = [10.0; 6];
+        let r = wilcoxon_signed_rank(&x, &y);
+        assert!(r.exact);
+        assert_eq!(r.n, 6);
+        assert_eq!(r.statistic, 21.0);
+        assert_eq!(r.expected, 10.5);
+        assert!(close(r.p_value, 0.031_25, 1e-12));
+
+        // При 5 парах минимально достижимый p = 2 / 2^5
+        let r = wilcoxon_signed_rank(&x[..5], &y[..5]);
+        assert!(close(r.p_value, 0.0625, 1e-12));
+    }
+
+    #[test]
+    fn wilcoxon_drops_zeros_and_snaps_ties() {
+        // Разности +1 и -(1 + 1e-12) -- связка (ранги 1.5), нулевая разность отбрасывается
+        let x = [2.0, 1.0, 5.0, 13.0];
+        let y = [1.0, 2.0 + 1e-12, 5.0, 10.0];
+        let r = wilcoxon_signed_rank(&x, &y);
+        assert_eq!(r.n, 3);
+        assert_eq!(r.statistic, 4.5);
+        // Удвоенные ранги {3, 3, 6}: суммы 0, 3, 3, 6, 6, 9, 9, 12, 2W+ = 9, P(>= 9) = 3 / 8
+        assert!(close(r.p_value, 0.75, 1e-12));
+    }
+
+    #[test]
+    fn mann_whitney_exact_with_ties() {
+        // Ранги объединенной выборки: 1, 3, 3, 3, 5, 6; R1 = 7, U = 1;
+        // из 20 разбиений 3 дают R1 <= 7, p = 2 * 3 / 20
+        let r = mann_whitney_u(&[1.0, 2.0, 2.0], &[2.0, 3.0, 4.0]);
+        assert!(r.exact);
+        assert_eq!(r.statistic, 1.0);
+        assert_eq!(r.expected, 4.5);
+        assert!(close(r.p_value, 0.3, 1e-12));
+
+        // Без связок и полного разделения: p = 2 / C(6, 3)
+        let r = mann_whitney_u(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]);
+        assert_eq!(r.statistic, 0.0);
+        assert!(close(r.p_value, 0.1, 1e-12));
+    }
+
+    #[test]
+    fn chi2_sf_known_quantiles() {
+        // Табличные 95%-квантили хи-квадрат
+        assert!(close(chi2_sf(3.841_459, 1.0), 0.05, 1e-6));
+        assert!(close(chi2_sf(5.991_465, 2.0), 0.05, 1e-6));
+        assert!(close(chi2_sf(11.070_498, 5.0), 0.05, 1e-6));
+        assert!(close(chi2_sf(6.634_897, 1.0), 0.01, 1e-6));
+        // При 2 степенях свободы Q = exp(-x / 2)
+        assert!(close(chi2_sf(2.0, 2.0), (-1.0f64).exp(), 1e-12));
+        assert_eq!(chi2_sf(0.0, 3.0), 1.0);
+    }
+
+    #[test]
+    fn erfc_and_ln_gamma() {
+        assert!(close(erfc(0.0), 1.0, 1e-7));
+        assert!(close(erfc(1.0), 0.157_299_207, 1e-7));
+        assert!(close(erfc(-1.0), 1.842_700_793, 1e-7));
+        assert!(close(ln_gamma(5.0), 24.0f64.ln(), 1e-12));
+        assert!(close(
+            ln_gamma(0.5),
+            std::f64::consts::PI.sqrt().ln(),
+            1e-12
+        ));
+    }
+}
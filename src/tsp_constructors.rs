@@ -0,0 +1,462 @@
+use crate::tsp_data::TspData;
+
+/// Эвристика построения начального тура
+#[derive(Clone, Copy, Debug)]
+pub enum Construction {
+    /// Ближайший сосед из вершины 0
+    NearestNeighbour,
+    /// Жадное паросочетание ребер (Greedy Edge)
+    GreedyEdge,
+    NearestInsertion,
+    CheapestInsertion,
+    FarthestInsertion,
+    /// Christofides: MST + жадное паросочетание нечетных вершин + эйлеров цикл
+    Christofides,
+    /// Порядок обхода кривой Гильберта, O(n log n)
+    SpaceFillingCurve,
+}
+
+pub fn build_tour(data: &TspData, candidates: &[Vec<usize>], method: Construction) -> Vec<usize> {
+    if data.n < 3 {
+        return (0..data.n).collect();
+    }
+    match method {
+        Construction::NearestNeighbour => greedy_initial_tour(data, candidates),
+        Construction::GreedyEdge => greedy_edge_tour(data, candidates),
+        Construction::NearestInsertion => insertion_tour(data, Insertion::Nearest),
+        Construction::CheapestInsertion => cheapest_insertion_tour(data),
+        Construction::FarthestInsertion => insertion_tour(data, Insertion::Farthest),
+        Construction::Christofides => christofides_tour(data, candidates),
+        Construction::SpaceFillingCurve => space_filling_curve_tour(data),
+    }
+}
+
+pub(crate) fn greedy_initial_tour(data: &TspData, candidates: &[Vec<usize>]) -> Vec<usize> {
+    nearest_neighbour_tour(data, candidates, 0)
+}
+
+// Ближайший сосед из произвольной стартовой вершины
+pub(crate) fn nearest_neighbour_tour(
+    data: &TspData,
+    candidates: &[Vec<usize>],
+    start: usize,
+) -> Vec<usize> {
+    let n = data.n;
+    let mut unvisited = vec![true; n];
+    let mut tour = Vec::with_capacity(n);
+    let mut curr = start;
+    tour.push(start);
+    unvisited[start] = false;
+
+    for _ in 1..n {
+        let mut next_node = 0;
+        let mut found = false;
+        for &cand in &candidates[curr] {
+            if unvisited[cand] {
+                next_node = cand;
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            let mut best_dist = f64::INFINITY;
+            for (j, _) in unvisited.iter().enumerate().filter(|&(_, &u)| u) {
+                let d = data.dist(curr, j);
+                if d < best_dist {
+                    best_dist = d;
+                    next_node = j;
+                }
+            }
+        }
+        curr = next_node;
+        tour.push(curr);
+        unvisited[curr] = false;
+    }
+    tour
+}
+
+fn find(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+// Обход графа степени <= 2, заданного списками смежности, начиная с `start`
+fn walk_path(adj: &[Vec<usize>], start: usize, visited: &mut [bool], out: &mut Vec<usize>) {
+    let (mut prev, mut curr) = (usize::MAX, start);
+    loop {
+        visited[curr] = true;
+        out.push(curr);
+        match adj[curr]
+            .iter()
+            .copied()
+            .find(|&v| v != prev && !visited[v])
+        {
+            Some(next) => {
+                prev = curr;
+                curr = next;
+            }
+            None => return,
+        }
+    }
+}
+
+// Greedy Edge: берем кратчайшие ребра из списков кандидатов, пока степень <= 2 и нет циклов,
+// оставшиеся фрагменты сшиваем ближайшим соседом по концам
+pub fn greedy_edge_tour(data: &TspData, candidates: &[Vec<usize>]) -> Vec<usize> {
+    let n = data.n;
+    let mut edges: Vec<(f64, usize, usize)> = Vec::with_capacity(n * 10);
+    for (u, list) in candidates.iter().enumerate() {
+        for &v in list {
+            if u < v {
+                edges.push((data.dist(u, v), u, v));
+            }
+        }
+    }
+    edges.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let mut adj: Vec<Vec<usize>> = vec![Vec::with_capacity(2); n];
+    let mut parent: Vec<usize> = (0..n).collect();
+    for (_, u, v) in edges {
+        if adj[u].len() >= 2 || adj[v].len() >= 2 {
+            continue;
+        }
+        let (ru, rv) = (find(&mut parent, u), find(&mut parent, v));
+        if ru == rv {
+            continue;
+        }
+        parent[ru] = rv;
+        adj[u].push(v);
+        adj[v].push(u);
+    }
+
+    // Фрагменты-пути (одиночные вершины -- тоже фрагменты)
+    let mut visited = vec![false; n];
+    let mut fragments: Vec<Vec<usize>> = Vec::new();
+    for v in 0..n {
+        if !visited[v] && adj[v].len() < 2 {
+            let mut path = Vec::new();
+            walk_path(&adj, v, &mut visited, &mut path);
+            fragments.push(path);
+        }
+    }
+
+    // Сшиваем: от конца текущего фрагмента к ближайшему концу оставшихся
+    let mut tour = Vec::with_capacity(n);
+    let mut used = vec![false; fragments.len()];
+    let mut curr = 0;
+    for _ in 0..fragments.len() {
+        used[curr] = true;
+        tour.extend_from_slice(&fragments[curr]);
+        let tail = *tour.last().unwrap();
+        let mut best: Option<(f64, usize, bool)> = None;
+        for (k, frag) in fragments.iter().enumerate().filter(|&(k, _)| !used[k]) {
+            let (head, end) = (frag[0], *frag.last().unwrap());
+            for (city, reversed) in [(head, false), (end, true)] {
+                let d = data.dist(tail, city);
+                if best.is_none_or(|b| d < b.0) {
+                    best = Some((d, k, reversed));
+                }
+            }
+        }
+        match best {
+            Some((_, k, reversed)) => {
+                if reversed {
+                    fragments[k].reverse();
+                }
+                curr = k;
+            }
+            None => break,
+        }
+    }
+    tour
+}
+
+#[derive(Clone, Copy)]
+enum Insertion {
+    Nearest,
+    Farthest,
+}
+
+// Лучшая позиция вставки `city` в тур: (прирост длины, индекс предшественника)
+fn best_insertion(data: &TspData, tour: &[usize], city: usize) -> (f64, usize) {
+    let m = tour.len();
+    let mut best = (f64::INFINITY, 0);
+    for i in 0..m {
+        let (a, b) = (tour[i], tour[(i + 1) % m]);
+        let delta = data.dist(a, city) + data.dist(city, b) - data.dist(a, b);
+        if delta < best.0 {
+            best = (delta, i);
+        }
+    }
+    best
+}
+
+// Nearest / Farthest insertion за O(n^2): расстояние до тура поддерживается инкрементально
+fn insertion_tour(data: &TspData, rule: Insertion) -> Vec<usize> {
+    let n = data.n;
+    let mut in_tour = vec![false; n];
+    let mut to_tour = vec![f64::INFINITY; n];
+
+    // Старт: вершина 0 и самая близкая / дальняя от нее
+    let first = 0;
+    let second = (1..n)
+        .map(|j| (data.dist(first, j), j))
+        .min_by(|a, b| match rule {
+            Insertion::Nearest => a.0.partial_cmp(&b.0).unwrap(),
+            Insertion::Farthest => b.0.partial_cmp(&a.0).unwrap(),
+        })
+        .unwrap()
+        .1;
+    let mut tour = vec![first, second];
+    for &c in &tour {
+        in_tour[c] = true;
+        for (j, d) in to_tour.iter_mut().enumerate() {
+            *d = d.min(data.dist(c, j));
+        }
+    }
+
+    while tour.len() < n {
+        let outside = (0..n).filter(|&j| !in_tour[j]);
+        let city = match rule {
+            Insertion::Nearest => {
+                outside.min_by(|&a, &b| to_tour[a].partial_cmp(&to_tour[b]).unwrap())
+            }
+            Insertion::Farthest => {
+                outside.max_by(|&a, &b| to_tour[a].partial_cmp(&to_tour[b]).unwrap())
+            }
+        }
+        .unwrap();
+
+        let (_, i) = best_insertion(data, &tour, city);
+        tour.insert(i + 1, city);
+        in_tour[city] = true;
+        for (j, d) in to_tour.iter_mut().enumerate() {
+            *d = d.min(data.dist(city, j));
+        }
+    }
+    tour
+}
+
+// Cheapest insertion: каждая вершина помнит лучшее ребро для вставки;
+// полный пересчет нужен только тем, чье ребро было разрушено
+fn cheapest_insertion_tour(data: &TspData) -> Vec<usize> {
+    let n = data.n;
+    // Тур как односвязный список: next[a] -- следующая за a вершина
+    let mut next = vec![usize::MAX; n];
+    let far = (1..n)
+        .max_by(|&a, &b| data.dist(0, a).partial_cmp(&data.dist(0, b)).unwrap())
+        .unwrap();
+    next[0] = far;
+    next[far] = 0;
+    let mut members = vec![0, far];
+
+    let cost = |next: &[usize], a: usize, c: usize| {
+        let b = next[a];
+        data.dist(a, c) + data.dist(c, b) - data.dist(a, b)
+    };
+
+    // (стоимость, после какой вершины вставлять)
+    let mut best: Vec<(f64, usize)> = vec![(f64::INFINITY, 0); n];
+    for c in (0..n).filter(|&c| next[c] == usize::MAX) {
+        for &a in &members {
+            let d = cost(&next, a, c);
+            if d < best[c].0 {
+                best[c] = (d, a);
+            }
+        }
+    }
+
+    for _ in 2..n {
+        let city = (0..n)
+            .filter(|&c| next[c] == usize::MAX)
+            .min_by(|&a, &b| best[a].0.partial_cmp(&best[b].0).unwrap())
+            .unwrap();
+        let a = best[city].1;
+        next[city] = next[a];
+        next[a] = city;
+        members.push(city);
+
+        for c in (0..n).filter(|&c| next[c] == usize::MAX) {
+            if best[c].1 == a {
+                // Ребро (a, b) разрушено -- пересчитываем с нуля
+                best[c] = (f64::INFINITY, 0);
+                for &m in &members {
+                    let d = cost(&next, m, c);
+                    if d < best[c].0 {
+                        best[c] = (d, m);
+                    }
+                }
+            } else {
+                for m in [a, city] {
+                    let d = cost(&next, m, c);
+                    if d < best[c].0 {
+                        best[c] = (d, m);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut tour = Vec::with_capacity(n);
+    let mut curr = 0;
+    for _ in 0..n {
+        tour.push(curr);
+        curr = next[curr];
+    }
+    tour
+}
+
+// Christofides с жадным (а не оптимальным) паросочетанием нечетных вершин
+fn christofides_tour(data: &TspData, candidates: &[Vec<usize>]) -> Vec<usize> {
+    let n = data.n;
+
+    // 1. MST алгоритмом Прима на полном графе, O(n^2) без матрицы
+    let mut adj: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut in_tree = vec![false; n];
+    let mut key = vec![f64::INFINITY; n];
+    let mut link = vec![0; n];
+    key[0] = 0.0;
+    for _ in 0..n {
+        let u = (0..n)
+            .filter(|&v| !in_tree[v])
+            .min_by(|&a, &b| key[a].partial_cmp(&key[b]).unwrap())
+            .unwrap();
+        in_tree[u] = true;
+        if u != 0 {
+            adj[u].push(link[u]);
+            adj[link[u]].push(u);
+        }
+        for v in 0..n {
+            if !in_tree[v] {
+                let d = data.dist(u, v);
+                if d < key[v] {
+                    key[v] = d;
+                    link[v] = u;
+                }
+            }
+        }
+    }
+
+    // 2. Жадное паросочетание нечетных вершин: сначала по спискам кандидатов,
+    // остаток -- ближайший свободный
+    let mut odd = vec![false; n];
+    for v in 0..n {
+        odd[v] = adj[v].len() % 2 == 1;
+    }
+    let mut pairs: Vec<(f64, usize, usize)> = Vec::new();
+    for u in (0..n).filter(|&u| odd[u]) {
+        for &v in &candidates[u] {
+            if u < v && odd[v] {
+                pairs.push((data.dist(u, v), u, v));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let mut matched = vec![false; n];
+    for (_, u, v) in pairs {
+        if !matched[u] && !matched[v] {
+            matched[u] = true;
+            matched[v] = true;
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    let rest: Vec<usize> = (0..n).filter(|&v| odd[v] && !matched[v]).collect();
+    for &u in &rest {
+        if matched[u] {
+            continue;
+        }
+        let v = rest
+            .iter()
+            .copied()
+            .filter(|&v| v != u && !matched[v])
+            .min_by(|&a, &b| data.dist(u, a).partial_cmp(&data.dist(u, b)).unwrap())
+            .unwrap();
+        matched[u] = true;
+        matched[v] = true;
+        adj[u].push(v);
+        adj[v].push(u);
+    }
+
+    // 3. Эйлеров цикл (Хирхольцер) и срезание повторов
+    let mut used_edges: Vec<Vec<bool>> = adj.iter().map(|l| vec![false; l.len()]).collect();
+    let mut ptr = vec![0; n];
+    let mut stack = vec![0];
+    let mut visited = vec![false; n];
+    let mut tour = Vec::with_capacity(n);
+    while let Some(&u) = stack.last() {
+        while ptr[u] < adj[u].len() && used_edges[u][ptr[u]] {
+            ptr[u] += 1;
+        }
+        if ptr[u] == adj[u].len() {
+            stack.pop();
+            if !visited[u] {
+                visited[u] = true;
+                tour.push(u);
+            }
+            continue;
+        }
+        let k = ptr[u];
+        let v = adj[u][k];
+        used_edges[u][k] = true;
+        // Помечаем парное ребро (v, u)
+        let back = (0..adj[v].len())
+            .find(|&j| adj[v][j] == u && !used_edges[v][j])
+            .unwrap();
+        used_edges[v][back] = true;
+        stack.push(v);
+    }
+    tour
+}
+
+// Индекс точки на кривой Гильберта порядка `order` (классический xy2d)
+fn hilbert_index(order: u32, mut x: u64, mut y: u64) -> u64 {
+    let side = 1u64 << order;
+    let mut d = 0;
+    let mut s = side / 2;
+    while s > 0 {
+        let rx = u64::from(x & s > 0);
+        let ry = u64::from(y & s > 0);
+        d += s * s * ((3 * rx) ^ ry);
+        if ry == 0 {
+            if rx == 1 {
+                x = side - 1 - x;
+                y = side - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+pub fn space_filling_curve_tour(data: &TspData) -> Vec<usize> {
+    let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(x, y) in &data.coords {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    // Одинаковый масштаб по осям, чтобы не искажать геометрию
+    let span = (max_x - min_x).max(max_y - min_y).max(1e-12);
+    let order = 16;
+    let cells = ((1u64 << order) - 1) as f64;
+
+    let mut keyed: Vec<(u64, usize)> = data
+        .coords
+        .iter()
+        .enumerate()
+        .map(|(i, &(x, y))| {
+            let gx = ((x - min_x) / span * cells) as u64;
+            let gy = ((y - min_y) / span * cells) as u64;
+            (hilbert_index(order, gx, gy), i)
+        })
+        .collect();
+    keyed.sort_unstable();
+    keyed.into_iter().map(|(_, i)| i).collect()
+}
@@ -1,6 +1,8 @@
+use std::fmt;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
 use std::path::Path;
+use tracing::warn;
 
 #[derive(Clone)]
 pub struct TspData {
@@ -11,32 +13,30 @@ pub struct TspData {
 impl TspData {
     pub fn new(filename: &str) -> io::Result<Self> {
         let path = Path::new(filename);
-        let file = File::open(&path)?;
+        let file = File::open(path)?;
         let reader = BufReader::new(file);
         let mut lines = reader.lines();
 
         let mut n = 0;
         let mut coords = Vec::new();
 
-        if let Some(Ok(line)) = lines.next() {
-            if let Ok(val) = line.trim().parse::<usize>() {
-                n = val;
-            }
+        if let Some(Ok(line)) = lines.next()
+            && let Ok(val) = line.trim().parse::<usize>()
+        {
+            n = val;
         }
 
-        for line in lines {
-            if let Ok(l) = line {
-                let parts: Vec<&str> = l.split_whitespace().collect();
-                if parts.len() >= 2 {
-                    let x = parts[0].parse::<f64>().unwrap_or(0.0);
-                    let y = parts[1].parse::<f64>().unwrap_or(0.0);
-                    coords.push((x, y));
-                }
+        for l in lines.map_while(Result::ok) {
+            let parts: Vec<&str> = l.split_whitespace().collect();
+            if parts.len() >= 2 {
+                let x = parts[0].parse::<f64>().unwrap_or(0.0);
+                let y = parts[1].parse::<f64>().unwrap_or(0.0);
+                coords.push((x, y));
             }
         }
 
         if coords.len() != n {
-            eprintln!("Warning: Expected {} points, found {}", n, coords.len());
+            warn!(expected = n, found = coords.len(), "point count mismatch");
             n = coords.len();
         }
 
@@ -62,3 +62,189 @@ impl TspData {
         length
     }
 }
+
+/// Нарушение, найденное при проверке тура
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TourError {
+    /// В туре не столько городов, сколько в экземпляре
+    WrongSize { expected: usize, found: usize },
+    /// Номер города вне `0..n`
+    OutOfRange { position: usize, city: usize },
+    /// Город встречается второй раз (позиции первого и повторного вхождения)
+    Duplicate {
+        city: usize,
+        first: usize,
+        position: usize,
+    },
+    /// Города, которых нет в туре
+    Missing(Vec<usize>),
+}
+
+impl fmt::Display for TourError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TourError::WrongSize { expected, found } => {
+                write!(f, "tour has {} cities, instance has {}", found, expected)
+            }
+            TourError::OutOfRange { position, city } => {
+                write!(f, "city {} at position {} is out of range", city, position)
+            }
+            TourError::Duplicate {
+                city,
+                first,
+                position,
+            } => write!(
+                f,
+                "city {} at position {} already visited at position {}",
+                city, position, first
+            ),
+            TourError::Missing(cities) if cities.len() > 10 => {
+                write!(
+                    f,
+                    "{} cities missing, first {:?}",
+                    cities.len(),
+                    &cities[..10]
+                )
+            }
+            TourError::Missing(cities) => write!(f, "cities {:?} missing", cities),
+        }
+    }
+}
+
+/// Все нарушения в туре; в тексте -- первые несколько
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidTour(pub Vec<TourError>);
+
+impl fmt::Display for InvalidTour {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const SHOWN: usize = 5;
+        write!(f, "Invalid tour: ")?;
+        for (k, e) in self.0.iter().take(SHOWN).enumerate() {
+            if k > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", e)?;
+        }
+        if self.0.len() > SHOWN {
+            write!(f, " (and {} more)", self.0.len() - SHOWN)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InvalidTour {}
+
+/// Проверяет, что тур -- перестановка `0..n`: размер, диапазон номеров, повторы и пропуски
+pub fn validate_permutation(tour: &[usize], n: usize) -> Result<(), InvalidTour> {
+    let mut errors = Vec::new();
+    if tour.len() != n {
+        errors.push(TourError::WrongSize {
+            expected: n,
+            found: tour.len(),
+        });
+    }
+    let mut seen = vec![None; n];
+    for (position, &city) in tour.iter().enumerate() {
+        match seen.get(city) {
+            None => errors.push(TourError::OutOfRange { position, city }),
+            Some(&Some(first)) => errors.push(TourError::Duplicate {
+                city,
+                first,
+                position,
+            }),
+            Some(None) => seen[city] = Some(position),
+        }
+    }
+    let missing: Vec<usize> = (0..n).filter(|&c| seen[c].is_none()).collect();
+    if !missing.is_empty() {
+        errors.push(TourError::Missing(missing));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(InvalidTour(errors))
+    }
+}
+
+impl TspData {
+    /// Длина тура, пересчитанная по метрике экземпляра, только для корректной перестановки
+    pub fn validate_tour(&self, tour: &[usize]) -> Result<f64, InvalidTour> {
+        validate_permutation(tour, self.n)?;
+        Ok(self.calculate_tour_length(tour))
+    }
+}
+
+/// Строка файла ответов `answers/*.txt`: `{test_id} {points} [a, b, ...]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Answer {
+    pub test_id: usize,
+    pub points: u32,
+    pub tour: Vec<usize>,
+}
+
+fn parse_cities(body: &str, filename: &str) -> io::Result<Vec<usize>> {
+    body.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+        .map(|t| {
+            t.parse::<usize>().map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Bad city index '{}' in {}", t, filename),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Все строки файла ответов
+pub fn read_answers(filename: &str) -> io::Result<Vec<Answer>> {
+    let content = std::fs::read_to_string(filename)?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(k, line)| {
+            let invalid = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}:{}: expected '<test> <points> [tour]'", filename, k + 1),
+                )
+            };
+            let start = line.find('[').ok_or_else(invalid)?;
+            let end = line.rfind(']').ok_or_else(invalid)?;
+            let mut head = line[..start].split_whitespace();
+            let test_id = head
+                .next()
+                .and_then(|t| t.parse().ok())
+                .ok_or_else(invalid)?;
+            let points = head
+                .next()
+                .and_then(|t| t.parse().ok())
+                .ok_or_else(invalid)?;
+            Ok(Answer {
+                test_id,
+                points,
+                tour: parse_cities(&line[start + 1..end], filename)?,
+            })
+        })
+        .collect()
+}
+
+/// Читает тур из файла: либо строку `answers/*.txt` вида `{id} {points} [a, b, ...]`
+/// (с `test_id` -- строку с этим номером, иначе первую), либо просто список номеров городов
+pub fn read_tour(filename: &str, test_id: Option<usize>) -> io::Result<Vec<usize>> {
+    let content = std::fs::read_to_string(filename)?;
+    if !content.contains('[') {
+        return parse_cities(&content, filename);
+    }
+    read_answers(filename)?
+        .into_iter()
+        .find(|a| test_id.is_none_or(|id| a.test_id == id))
+        .map(|a| a.tour)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("No tour for test {:?} in {}", test_id, filename),
+            )
+        })
+}
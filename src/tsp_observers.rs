@@ -0,0 +1,480 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{Level, debug, enabled, info};
+
+/// Показатели одной итерации; `None` -- алгоритм такую величину не считает
+#[derive(Clone, Debug, Default)]
+pub struct IterationStats {
+    pub iteration: usize,
+    /// Лучшая длина за весь прогон
+    pub best: f64,
+    /// Лучшая длина среди решений этой итерации
+    pub iteration_best: Option<f64>,
+    /// Средняя длина решений итерации (муравьев, частиц, особей)
+    pub mean: Option<f64>,
+    /// ACS/MMAS: средняя нормированная энтропия феромона на ребрах-кандидатах, 1 -- равномерно
+    pub entropy: Option<f64>,
+    /// ACS/MMAS: средний lambda-branching factor (lambda = 0.05) по спискам кандидатов
+    pub branching: Option<f64>,
+    /// Разнообразие роя / популяции (для перестановок -- доля ребер, отличных от лучшего тура)
+    pub diversity: Option<f64>,
+}
+
+/// Наблюдатель за ходом солвера. Все методы необязательные; солвер вызывает их
+/// из `run`, вместо того чтобы печатать сам.
+pub trait SolverObserver {
+    /// Перед первой итерацией: имя алгоритма и строка с его параметрами
+    fn on_start(&mut self, _solver: &str, _params: &str) {}
+    /// После каждой итерации (поколения, цикла)
+    fn on_iteration(&mut self, _stats: &IterationStats) {}
+    /// Найден новый лучший тур
+    fn on_improvement(&mut self, _iteration: usize, _tour: &[usize], _length: f64) {}
+    /// Событие, специфичное для алгоритма: стагнация, подогрев, прерывание
+    fn on_event(&mut self, _iteration: usize, _message: &str) {}
+    fn on_finish(&mut self, _iterations: usize, _best: f64) {}
+    /// Нужны ли наблюдателю `entropy` и `branching`: их подсчет для ACS/MMAS стоит O(n*k)
+    /// на итерацию, поэтому солвер заполняет эти поля только по запросу
+    fn wants_pheromone_stats(&self) -> bool {
+        false
+    }
+}
+
+/// Несколько наблюдателей сразу
+impl SolverObserver for Vec<Box<dyn SolverObserver>> {
+    fn on_start(&mut self, solver: &str, params: &str) {
+        for o in self.iter_mut() {
+            o.on_start(solver, params);
+        }
+    }
+    fn on_iteration(&mut self, stats: &IterationStats) {
+        for o in self.iter_mut() {
+            o.on_iteration(stats);
+        }
+    }
+    fn on_improvement(&mut self, iteration: usize, tour: &[usize], length: f64) {
+        for o in self.iter_mut() {
+            o.on_improvement(iteration, tour, length);
+        }
+    }
+    fn on_event(&mut self, iteration: usize, message: &str) {
+        for o in self.iter_mut() {
+            o.on_event(iteration, message);
+        }
+    }
+    fn on_finish(&mut self, iterations: usize, best: f64) {
+        for o in self.iter_mut() {
+            o.on_finish(iterations, best);
+        }
+    }
+    fn wants_pheromone_stats(&self) -> bool {
+        self.iter().any(|o| o.wants_pheromone_stats())
+    }
+}
+
+/// Простой вывод в консоль через `println!`, без подписчика `tracing`
+pub struct ConsoleObserver {
+    /// Печатать текущий лучший результат каждые `progress_every` итераций, 0 -- никогда
+    pub progress_every: usize,
+    start: Instant,
+}
+
+impl Default for ConsoleObserver {
+    fn default() -> Self {
+        ConsoleObserver {
+            progress_every: 100,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl SolverObserver for ConsoleObserver {
+    fn on_start(&mut self, solver: &str, params: &str) {
+        self.start = Instant::now();
+        println!("Starting {} ({})...", solver, params);
+    }
+
+    fn on_iteration(&mut self, stats: &IterationStats) {
+        if self.progress_every > 0 && stats.iteration.is_multiple_of(self.progress_every) {
+            println!("Iter {}: Best = {:.2}", stats.iteration, stats.best);
+        }
+    }
+
+    fn on_improvement(&mut self, iteration: usize, _tour: &[usize], length: f64) {
+        println!("Iter {}: NEW RECORD {:.2}", iteration, length);
+    }
+
+    fn on_event(&mut self, iteration: usize, message: &str) {
+        println!("Iter {}: {}", iteration, message);
+    }
+
+    fn on_finish(&mut self, iterations: usize, best: f64) {
+        println!(
+            "Done in {:.2?} ({} iterations). Best: {:.2}",
+            self.start.elapsed(),
+            iterations,
+            best
+        );
+    }
+}
+
+/// События солвера как записи `tracing` (наблюдатель по умолчанию): новые рекорды
+/// и события алгоритма -- `info`, каждая итерация -- `debug`
+pub struct LogObserver {
+    start: Instant,
+}
+
+impl Default for LogObserver {
+    fn default() -> Self {
+        LogObserver {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl SolverObserver for LogObserver {
+    fn on_start(&mut self, solver: &str, params: &str) {
+        self.start = Instant::now();
+        info!(solver, params, "starting {}", solver);
+    }
+
+    fn on_iteration(&mut self, stats: &IterationStats) {
+        debug!(
+            iteration = stats.iteration,
+            best = stats.best,
+            iteration_best = ?stats.iteration_best,
+            mean = ?stats.mean,
+            entropy = ?stats.entropy,
+            branching = ?stats.branching,
+            diversity = ?stats.diversity,
+            "iteration done"
+        );
+    }
+
+    fn wants_pheromone_stats(&self) -> bool {
+        enabled!(Level::DEBUG)
+    }
+
+    fn on_improvement(&mut self, iteration: usize, _tour: &[usize], length: f64) {
+        info!(iteration, length, "new record {:.2}", length);
+    }
+
+    fn on_event(&mut self, iteration: usize, message: &str) {
+        info!(iteration, "{}", message);
+    }
+
+    fn on_finish(&mut self, iterations: usize, best: f64) {
+        let elapsed = self.start.elapsed();
+        info!(
+            iterations,
+            best,
+            elapsed_ms = elapsed.as_secs_f64() * 1e3,
+            "done in {:.2?}, best {:.2}",
+            elapsed,
+            best
+        );
+    }
+}
+
+/// Журнал событий в формате JSON lines: один объект на строку
+pub struct JsonLinesObserver<W: Write> {
+    writer: W,
+    /// Писать ли сам тур в событиях `improvement` (на больших графах -- мегабайты)
+    pub include_tours: bool,
+    start: Instant,
+}
+
+impl JsonLinesObserver<BufWriter<File>> {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> JsonLinesObserver<W> {
+    pub fn new(writer: W) -> Self {
+        JsonLinesObserver {
+            writer,
+            include_tours: false,
+            start: Instant::now(),
+        }
+    }
+
+    // Ошибки записи журнала не должны останавливать солвер
+    fn emit(&mut self, event: &str, fields: &str) {
+        let _ = writeln!(
+            self.writer,
+            "{{\"event\":\"{}\",\"elapsed\":{:.6}{}}}",
+            event,
+            self.start.elapsed().as_secs_f64(),
+            fields
+        );
+    }
+}
+
+impl<W: Write> SolverObserver for JsonLinesObserver<W> {
+    fn on_start(&mut self, solver: &str, params: &str) {
+        self.start = Instant::now();
+        let fields = format!(
+            ",\"solver\":\"{}\",\"params\":\"{}\"",
+            json_escape(solver),
+            json_escape(params)
+        );
+        self.emit("start", &fields);
+    }
+
+    fn on_iteration(&mut self, stats: &IterationStats) {
+        let fields = format!(
+            ",\"iteration\":{},\"best\":{},\"iteration_best\":{},\"mean\":{},\"entropy\":{},\"branching\":{},\"diversity\":{}",
+            stats.iteration,
+            stats.best,
+            json_opt(stats.iteration_best),
+            json_opt(stats.mean),
+            json_opt(stats.entropy),
+            json_opt(stats.branching),
+            json_opt(stats.diversity)
+        );
+        self.emit("iteration", &fields);
+    }
+
+    fn wants_pheromone_stats(&self) -> bool {
+        true
+    }
+
+    fn on_improvement(&mut self, iteration: usize, tour: &[usize], length: f64) {
+        let mut fields = format!(",\"iteration\":{},\"length\":{}", iteration, length);
+        if self.include_tours {
+            fields.push_str(&format!(",\"tour\":{:?}", tour));
+        }
+        self.emit("improvement", &fields);
+    }
+
+    fn on_event(&mut self, iteration: usize, message: &str) {
+        let fields = format!(
+            ",\"iteration\":{},\"message\":\"{}\"",
+            iteration,
+            json_escape(message)
+        );
+        self.emit("event", &fields);
+    }
+
+    fn on_finish(&mut self, iterations: usize, best: f64) {
+        self.emit(
+            "finish",
+            &format!(",\"iterations\":{},\"best\":{}", iterations, best),
+        );
+        let _ = self.writer.flush();
+    }
+}
+
+fn json_opt(value: Option<f64>) -> String {
+    value.map_or_else(|| "null".to_string(), |v| v.to_string())
+}
+
+pub(crate) fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Сводные метрики прогона
+#[derive(Clone, Debug, Default)]
+pub struct RunMetrics {
+    /// Параметры из `on_start`
+    pub params: String,
+    pub iterations: usize,
+    pub improvements: usize,
+    pub best: f64,
+    /// Итерация и время, когда был найден лучший тур
+    pub best_iteration: usize,
+    pub time_to_best: Duration,
+    pub elapsed: Duration,
+}
+
+/// Собирает `RunMetrics`; результат читается через `metrics()` после `run`
+pub struct MetricsObserver {
+    metrics: Arc<Mutex<RunMetrics>>,
+    start: Instant,
+}
+
+impl MetricsObserver {
+    pub fn new() -> Self {
+        MetricsObserver {
+            metrics: Arc::new(Mutex::new(RunMetrics::default())),
+            start: Instant::now(),
+        }
+    }
+
+    /// Общий доступ к метрикам: наблюдатель передается солверу во владение
+    pub fn metrics(&self) -> Arc<Mutex<RunMetrics>> {
+        Arc::clone(&self.metrics)
+    }
+}
+
+impl Default for MetricsObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SolverObserver for MetricsObserver {
+    fn on_start(&mut self, _solver: &str, params: &str) {
+        self.start = Instant::now();
+        *self.metrics.lock().unwrap() = RunMetrics {
+            params: params.to_string(),
+            best: f64::INFINITY,
+            ..RunMetrics::default()
+        };
+    }
+
+    fn on_iteration(&mut self, stats: &IterationStats) {
+        let mut m = self.metrics.lock().unwrap();
+        m.iterations = stats.iteration + 1;
+        m.best = m.best.min(stats.best);
+    }
+
+    fn on_improvement(&mut self, iteration: usize, _tour: &[usize], length: f64) {
+        let mut m = self.metrics.lock().unwrap();
+        m.improvements += 1;
+        m.best = length;
+        m.best_iteration = iteration;
+        m.time_to_best = self.start.elapsed();
+    }
+
+    fn on_finish(&mut self, iterations: usize, best: f64) {
+        let mut m = self.metrics.lock().unwrap();
+        m.iterations = iterations;
+        m.best = m.best.min(best);
+        m.elapsed = self.start.elapsed();
+    }
+}
+
+/// Запись истории сходимости: показатели итерации и время от старта
+#[derive(Clone, Debug)]
+pub struct IterationRecord {
+    pub elapsed: Duration,
+    pub stats: IterationStats,
+}
+
+/// История сходимости прогона с экспортом в CSV и JSON для построения графиков
+#[derive(Clone, Debug, Default)]
+pub struct ConvergenceHistory {
+    pub solver: String,
+    pub records: Vec<IterationRecord>,
+}
+
+impl ConvergenceHistory {
+    /// Пустые ячейки -- величина не считается алгоритмом
+    pub fn write_csv(&self, w: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            w,
+            "iteration,elapsed_s,best,iteration_best,mean,entropy,branching,diversity"
+        )?;
+        let cell = |v: Option<f64>| v.map_or_else(String::new, |v| v.to_string());
+        for r in &self.records {
+            let s = &r.stats;
+            writeln!(
+                w,
+                "{},{:.6},{},{},{},{},{},{}",
+                s.iteration,
+                r.elapsed.as_secs_f64(),
+                s.best,
+                cell(s.iteration_best),
+                cell(s.mean),
+                cell(s.entropy),
+                cell(s.branching),
+                cell(s.diversity)
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn write_json(&self, w: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            w,
+            "{{\"solver\":\"{}\",\"records\":[",
+            json_escape(&self.solver)
+        )?;
+        for (k, r) in self.records.iter().enumerate() {
+            let s = &r.stats;
+            writeln!(
+                w,
+                "{{\"iteration\":{},\"elapsed_s\":{:.6},\"best\":{},\"iteration_best\":{},\"mean\":{},\"entropy\":{},\"branching\":{},\"diversity\":{}}}{}",
+                s.iteration,
+                r.elapsed.as_secs_f64(),
+                s.best,
+                json_opt(s.iteration_best),
+                json_opt(s.mean),
+                json_opt(s.entropy),
+                json_opt(s.branching),
+                json_opt(s.diversity),
+                if k + 1 < self.records.len() { "," } else { "" }
+            )?;
+        }
+        writeln!(w, "]}}")
+    }
+
+    /// Запись в `<path>.csv` или `<path>.json` по расширению
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => self.write_json(&mut w)?,
+            _ => self.write_csv(&mut w)?,
+        }
+        w.flush()
+    }
+}
+
+/// Накапливает `ConvergenceHistory`; читается через `history()` после `run`
+pub struct HistoryRecorder {
+    history: Arc<Mutex<ConvergenceHistory>>,
+    start: Instant,
+}
+
+impl HistoryRecorder {
+    pub fn new() -> Self {
+        HistoryRecorder {
+            history: Arc::new(Mutex::new(ConvergenceHistory::default())),
+            start: Instant::now(),
+        }
+    }
+
+    pub fn history(&self) -> Arc<Mutex<ConvergenceHistory>> {
+        Arc::clone(&self.history)
+    }
+}
+
+impl Default for HistoryRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SolverObserver for HistoryRecorder {
+    fn on_start(&mut self, solver: &str, _params: &str) {
+        self.start = Instant::now();
+        let mut h = self.history.lock().unwrap();
+        h.solver = solver.to_string();
+        h.records.clear();
+    }
+
+    fn on_iteration(&mut self, stats: &IterationStats) {
+        self.history.lock().unwrap().records.push(IterationRecord {
+            elapsed: self.start.elapsed(),
+            stats: stats.clone(),
+        });
+    }
+
+    fn wants_pheromone_stats(&self) -> bool {
+        true
+    }
+}
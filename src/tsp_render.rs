@@ -0,0 +1,484 @@
+use crate::tsp_data::{TspData, validate_permutation};
+use crate::tsp_observers::SolverObserver;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(214, 39, 40);
+    pub const BLUE: Rgb = Rgb(31, 119, 180);
+    pub const ORANGE: Rgb = Rgb(255, 127, 14);
+
+    fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Как рисовать ребра тура
+#[derive(Clone, Debug)]
+pub struct Stroke {
+    pub color: Rgb,
+    /// Толщина в пикселях
+    pub width: f64,
+    /// Штрих и пробел в пикселях; `None` -- сплошная линия
+    pub dash: Option<(f64, f64)>,
+    pub opacity: f64,
+}
+
+impl Stroke {
+    /// Лучший тур: сплошная синяя линия поверх остальных
+    pub fn best() -> Self {
+        Stroke {
+            color: Rgb::BLUE,
+            width: 1.5,
+            dash: None,
+            opacity: 1.0,
+        }
+    }
+
+    /// Тур для сравнения (например, PSO): полупрозрачный оранжевый пунктир
+    pub fn reference() -> Self {
+        Stroke {
+            color: Rgb::ORANGE,
+            width: 1.0,
+            dash: Some((6.0, 4.0)),
+            opacity: 0.7,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RenderConfig {
+    pub width: u32,
+    pub height: u32,
+    /// Отступ от края до точек, пиксели
+    pub margin: f64,
+    pub background: Rgb,
+    pub point_color: Rgb,
+    /// Радиус точек-городов; `None` -- подбирается по числу городов
+    pub point_radius: Option<f64>,
+    pub title: Option<String>,
+    /// Подписи слоев с длинами туров (только в SVG, в PNG текст не рисуется)
+    pub legend: bool,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        RenderConfig {
+            width: 1000,
+            height: 1000,
+            margin: 40.0,
+            background: Rgb::WHITE,
+            point_color: Rgb::RED,
+            point_radius: None,
+            title: None,
+            legend: true,
+        }
+    }
+}
+
+struct Layer<'a> {
+    tour: &'a [usize],
+    label: String,
+    stroke: Stroke,
+}
+
+/// Картинка экземпляра: города и один или несколько туров поверх них.
+/// Слои рисуются в порядке добавления, поэтому лучший тур добавляют последним.
+pub struct TourPlot<'a> {
+    data: &'a TspData,
+    pub config: RenderConfig,
+    layers: Vec<Layer<'a>>,
+}
+
+impl<'a> TourPlot<'a> {
+    pub fn new(data: &'a TspData, config: RenderConfig) -> Self {
+        TourPlot {
+            data,
+            config,
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn add_tour(&mut self, tour: &'a [usize], label: &str, stroke: Stroke) -> &mut Self {
+        self.layers.push(Layer {
+            tour,
+            label: label.to_string(),
+            stroke,
+        });
+        self
+    }
+
+    /// Лучший тур поверх тура для сравнения
+    pub fn compare(
+        data: &'a TspData,
+        config: RenderConfig,
+        best: (&'a [usize], &str),
+        other: (&'a [usize], &str),
+    ) -> Self {
+        let mut plot = TourPlot::new(data, config);
+        plot.add_tour(other.0, other.1, Stroke::reference());
+        plot.add_tour(best.0, best.1, Stroke::best());
+        plot
+    }
+
+    fn validate(&self) -> io::Result<()> {
+        for layer in &self.layers {
+            validate_permutation(layer.tour, self.data.n).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{}: {}", layer.label, e),
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    // Равномерный масштаб по обеим осям, ось Y направлена вверх, как на графиках
+    fn projection(&self) -> impl Fn(usize) -> (f64, f64) + '_ {
+        let coords = &self.data.coords;
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for &(x, y) in coords {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
+        }
+        let w = self.config.width as f64 - 2.0 * self.config.margin;
+        let h = self.config.height as f64 - 2.0 * self.config.margin;
+        let span = (max_x - min_x).max(max_y - min_y).max(f64::EPSILON);
+        let scale = (w.min(h) / span).max(0.0);
+        // Центрируем по меньшей стороне
+        let off_x = self.config.margin + (w - (max_x - min_x) * scale) / 2.0;
+        let off_y = self.config.margin + (h - (max_y - min_y) * scale) / 2.0;
+        let height = self.config.height as f64;
+        move |i| {
+            let (x, y) = coords[i];
+            (
+                off_x + (x - min_x) * scale,
+                height - (off_y + (y - min_y) * scale),
+            )
+        }
+    }
+
+    fn point_radius(&self) -> f64 {
+        self.config
+            .point_radius
+            .unwrap_or_else(|| (3.0 * (200.0 / self.data.n.max(1) as f64).sqrt()).clamp(0.5, 3.0))
+    }
+
+    pub fn to_svg(&self) -> String {
+        let project = self.projection();
+        let (w, h) = (self.config.width, self.config.height);
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">"
+        );
+        let _ = writeln!(
+            svg,
+            "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+            self.config.background.hex()
+        );
+
+        for layer in &self.layers {
+            let mut points = String::with_capacity(layer.tour.len() * 16);
+            for &city in layer.tour {
+                let (x, y) = project(city);
+                let _ = write!(points, "{:.2},{:.2} ", x, y);
+            }
+            let dash = layer
+                .stroke
+                .dash
+                .map(|(on, off)| format!(" stroke-dasharray=\"{} {}\"", on, off))
+                .unwrap_or_default();
+            let _ = writeln!(
+                svg,
+                "<polygon points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-opacity=\"{}\" stroke-linejoin=\"round\"{}/>",
+                points.trim_end(),
+                layer.stroke.color.hex(),
+                layer.stroke.width,
+                layer.stroke.opacity,
+                dash
+            );
+        }
+
+        let r = self.point_radius();
+        let _ = writeln!(svg, "<g fill=\"{}\">", self.config.point_color.hex());
+        for i in 0..self.data.n {
+            let (x, y) = project(i);
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\"/>",
+                x, y, r
+            );
+        }
+        svg.push_str("</g>\n");
+
+        let mut text_y = 20.0;
+        if let Some(title) = &self.config.title {
+            let _ = writeln!(
+                svg,
+                "<text x=\"10\" y=\"{}\" font-family=\"sans-serif\" font-size=\"16\">{}</text>",
+                text_y,
+                xml_escape(title)
+            );
+            text_y += 20.0;
+        }
+        if self.config.legend {
+            for layer in self.layers.iter().rev() {
+                let length = self.data.calculate_tour_length(layer.tour);
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"10\" y=\"{}\" font-family=\"sans-serif\" font-size=\"13\" fill=\"{}\">{}: {:.2}</text>",
+                    text_y,
+                    layer.stroke.color.hex(),
+                    xml_escape(&layer.label),
+                    length
+                );
+                text_y += 17.0;
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn save_svg(&self, path: &Path) -> io::Result<()> {
+        self.validate()?;
+        fs::write(path, self.to_svg())
+    }
+
+    #[cfg(feature = "png")]
+    pub fn save_png(&self, path: &Path) -> io::Result<()> {
+        self.validate()?;
+        let canvas = self.rasterize();
+        let file = io::BufWriter::new(fs::File::create(path)?);
+        let mut encoder = png::Encoder::new(file, canvas.width, canvas.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&canvas.pixels))
+            .map_err(io::Error::other)
+    }
+
+    /// Формат по расширению: `.svg` или `.png` (если собрано с feature `png`)
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("svg") => self.save_svg(path),
+            #[cfg(feature = "png")]
+            Some("png") => self.save_png(path),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Unsupported image format: {}", path.display()),
+            )),
+        }
+    }
+
+    #[cfg(feature = "png")]
+    fn rasterize(&self) -> Canvas {
+        let project = self.projection();
+        let mut canvas = Canvas::new(
+            self.config.width,
+            self.config.height,
+            self.config.background,
+        );
+        for layer in &self.layers {
+            let mut phase = 0.0;
+            for i in 0..layer.tour.len() {
+                let a = project(layer.tour[i]);
+                let b = project(layer.tour[(i + 1) % layer.tour.len()]);
+                phase = canvas.line(a, b, &layer.stroke, phase);
+            }
+        }
+        let r = self.point_radius();
+        for i in 0..self.data.n {
+            canvas.disc(project(i), r, self.config.point_color, 1.0);
+        }
+        canvas
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Простейший растеризатор: линии -- цепочки дисков вдоль отрезка, без сглаживания
+#[cfg(feature = "png")]
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+#[cfg(feature = "png")]
+impl Canvas {
+    fn new(width: u32, height: u32, background: Rgb) -> Self {
+        let mut pixels = Vec::with_capacity((width * height * 3) as usize);
+        for _ in 0..width * height {
+            pixels.extend_from_slice(&[background.0, background.1, background.2]);
+        }
+        Canvas {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn blend(&mut self, x: i64, y: i64, color: Rgb, alpha: f64) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let k = ((y as u32 * self.width + x as u32) * 3) as usize;
+        for (c, v) in [color.0, color.1, color.2].into_iter().enumerate() {
+            let old = self.pixels[k + c] as f64;
+            self.pixels[k + c] = (old + (v as f64 - old) * alpha).round() as u8;
+        }
+    }
+
+    fn disc(&mut self, (cx, cy): (f64, f64), r: f64, color: Rgb, alpha: f64) {
+        let r = r.max(0.5);
+        let (x0, x1) = ((cx - r).floor() as i64, (cx + r).ceil() as i64);
+        let (y0, y1) = ((cy - r).floor() as i64, (cy + r).ceil() as i64);
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                let dx = x as f64 + 0.5 - cx;
+                let dy = y as f64 + 0.5 - cy;
+                if dx * dx + dy * dy <= r * r {
+                    self.blend(x, y, color, alpha);
+                }
+            }
+        }
+    }
+
+    // Возвращает фазу пунктира, чтобы штрихи продолжались через вершины тура
+    fn line(&mut self, a: (f64, f64), b: (f64, f64), stroke: &Stroke, mut phase: f64) -> f64 {
+        let len = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+        let steps = len.ceil().max(1.0) as usize;
+        let step = len / steps as f64;
+        // Ширина 1 -- одиночные пиксели, иначе диски (пересечения не затемняются повторно)
+        let mut last = (i64::MIN, i64::MIN);
+        for s in 0..=steps {
+            let t = s as f64 / steps as f64;
+            let p = (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+            let visible = match stroke.dash {
+                Some((on, off)) => phase % (on + off) < on,
+                None => true,
+            };
+            if visible {
+                if stroke.width <= 1.0 {
+                    let px = (p.0.floor() as i64, p.1.floor() as i64);
+                    if px != last {
+                        self.blend(px.0, px.1, stroke.color, stroke.opacity);
+                        last = px;
+                    }
+                } else if s % (stroke.width as usize).max(1) == 0 || s == steps {
+                    self.disc(p, stroke.width / 2.0, stroke.color, stroke.opacity);
+                }
+            }
+            if s < steps {
+                phase += step;
+            }
+        }
+        phase
+    }
+}
+
+/// Кадр анимации: тур после улучшения на итерации `iteration`
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub iteration: usize,
+    pub length: f64,
+    pub tour: Vec<usize>,
+}
+
+/// Наблюдатель, запоминающий улучшения лучшего тура как кадры анимации.
+/// Из нескольких улучшений за одну итерацию остается последнее; `stride` > 1
+/// прореживает кадры по номеру итерации, итоговый лучший тур сохраняется всегда.
+/// Кадры доступны через `frames()` и после передачи наблюдателя солверу.
+pub struct FrameRecorder {
+    pub stride: usize,
+    frames: Arc<Mutex<Vec<Frame>>>,
+    pending: Option<Frame>,
+}
+
+impl FrameRecorder {
+    pub fn new(stride: usize) -> Self {
+        FrameRecorder {
+            stride: stride.max(1),
+            frames: Arc::new(Mutex::new(Vec::new())),
+            pending: None,
+        }
+    }
+
+    pub fn frames(&self) -> Arc<Mutex<Vec<Frame>>> {
+        Arc::clone(&self.frames)
+    }
+}
+
+impl SolverObserver for FrameRecorder {
+    fn on_start(&mut self, _solver: &str, _params: &str) {
+        self.frames.lock().unwrap().clear();
+        self.pending = None;
+    }
+
+    fn on_improvement(&mut self, iteration: usize, tour: &[usize], length: f64) {
+        let frame = Frame {
+            iteration,
+            length,
+            tour: tour.to_vec(),
+        };
+        let mut frames = self.frames.lock().unwrap();
+        let last_it = frames.last().map(|f| f.iteration);
+        if last_it == Some(iteration) {
+            *frames.last_mut().unwrap() = frame;
+            self.pending = None;
+        } else if last_it.is_none_or(|it| iteration >= it + self.stride) {
+            frames.push(frame);
+            self.pending = None;
+        } else {
+            self.pending = Some(frame);
+        }
+    }
+
+    fn on_finish(&mut self, _iterations: usize, _best: f64) {
+        if let Some(frame) = self.pending.take() {
+            self.frames.lock().unwrap().push(frame);
+        }
+    }
+}
+
+/// Пишет кадры в `dir` как `frame_0000.<ext>` (`ext` -- `svg` или `png`);
+/// возвращает число записанных кадров
+pub fn render_frames(
+    data: &TspData,
+    config: &RenderConfig,
+    frames: &[Frame],
+    dir: &Path,
+    ext: &str,
+) -> io::Result<usize> {
+    fs::create_dir_all(dir)?;
+    for (k, frame) in frames.iter().enumerate() {
+        let mut config = config.clone();
+        let prefix = config
+            .title
+            .take()
+            .map(|t| format!("{} / ", t))
+            .unwrap_or_default();
+        config.title = Some(format!("{}iteration {}", prefix, frame.iteration));
+        let mut plot = TourPlot::new(data, config);
+        plot.add_tour(&frame.tour, "best", Stroke::best());
+        plot.save(&dir.join(format!("frame_{:04}.{}", k, ext)))?;
+    }
+    Ok(frames.len())
+}
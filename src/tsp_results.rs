@@ -0,0 +1,522 @@
+use crate::tsp_data::TspData;
+use crate::tsp_observers::json_escape;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Результат одного прогона солвера на экземпляре
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunResult {
+    pub test_id: usize,
+    /// Путь к файлу экземпляра, как он был передан солверу
+    pub instance: String,
+    pub algorithm: String,
+    /// Строка параметров алгоритма (та же, что в `SolverObserver::on_start`)
+    pub params: String,
+    /// Зерно генератора, если алгоритм запускался с фиксированным
+    pub seed: Option<u64>,
+    pub length: f64,
+    pub points: u32,
+    pub elapsed: Duration,
+    pub tour: Vec<usize>,
+}
+
+impl RunResult {
+    /// Тур -- перестановка городов экземпляра, записанная длина совпадает с пересчитанной
+    pub fn validate(&self, data: &TspData) -> Result<(), String> {
+        let length = data.validate_tour(&self.tour).map_err(|e| e.to_string())?;
+        if (length - self.length).abs() > 1e-9 * length.max(1.0) {
+            return Err(format!(
+                "Recorded length {} differs from recomputed {}",
+                self.length, length
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Набор результатов с записью и чтением в JSON или CSV
+#[derive(Clone, Debug, Default)]
+pub struct ResultSet {
+    pub results: Vec<RunResult>,
+}
+
+const CSV_HEADER: &str = "test_id,instance,algorithm,params,seed,length,points,elapsed_s,tour";
+
+impl ResultSet {
+    /// Тур в CSV -- номера городов через пробел
+    pub fn write_csv(&self, w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "{}", CSV_HEADER)?;
+        for r in &self.results {
+            let tour: Vec<String> = r.tour.iter().map(usize::to_string).collect();
+            writeln!(
+                w,
+                "{},{},{},{},{},{},{},{:.6},{}",
+                r.test_id,
+                csv_field(&r.instance),
+                csv_field(&r.algorithm),
+                csv_field(&r.params),
+                r.seed.map_or_else(String::new, |s| s.to_string()),
+                r.length,
+                r.points,
+                r.elapsed.as_secs_f64(),
+                tour.join(" ")
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn write_json(&self, w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "{{\"results\":[")?;
+        for (k, r) in self.results.iter().enumerate() {
+            let tour: Vec<String> = r.tour.iter().map(usize::to_string).collect();
+            writeln!(
+                w,
+                "{{\"test_id\":{},\"instance\":\"{}\",\"algorithm\":\"{}\",\"params\":\"{}\",\"seed\":{},\"length\":{},\"points\":{},\"elapsed_s\":{:.6},\"tour\":[{}]}}{}",
+                r.test_id,
+                json_escape(&r.instance),
+                json_escape(&r.algorithm),
+                json_escape(&r.params),
+                r.seed.map_or_else(|| "null".to_string(), |s| s.to_string()),
+                r.length,
+                r.points,
+                r.elapsed.as_secs_f64(),
+                tour.join(","),
+                if k + 1 < self.results.len() { "," } else { "" }
+            )?;
+        }
+        writeln!(w, "]}}")
+    }
+
+    /// Запись в `<path>.csv` или `<path>.json` по расширению
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => self.write_csv(&mut w)?,
+            _ => self.write_json(&mut w)?,
+        }
+        w.flush()
+    }
+
+    /// Чтение файла, записанного `save`; формат -- по расширению
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let results = match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => parse_csv(&content),
+            _ => parse_json(&content),
+        }
+        .map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })?;
+        Ok(ResultSet { results })
+    }
+
+    /// Проверяет каждый результат по его экземпляру (файлы читаются один раз);
+    /// возвращает ошибки в виде (индекс результата, описание)
+    pub fn validate(&self) -> Vec<(usize, String)> {
+        let mut instances: HashMap<&str, Result<TspData, String>> = HashMap::new();
+        let mut errors = Vec::new();
+        for (k, r) in self.results.iter().enumerate() {
+            let data = instances
+                .entry(r.instance.as_str())
+                .or_insert_with(|| TspData::new(&r.instance).map_err(|e| e.to_string()));
+            let checked = match data {
+                Ok(data) => r.validate(data),
+                Err(e) => Err(format!("Cannot load {}: {}", r.instance, e)),
+            };
+            if let Err(e) = checked {
+                errors.push((k, e));
+            }
+        }
+        errors
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// Разбиение строки CSV с учетом кавычек
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn parse_csv(content: &str) -> Result<Vec<RunResult>, String> {
+    let mut lines = content.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.trim() == CSV_HEADER => {}
+        _ => return Err("Missing or unknown CSV header".to_string()),
+    }
+    let mut results = Vec::new();
+    for (k, line) in lines.filter(|(_, l)| !l.trim().is_empty()) {
+        let f = split_csv_line(line);
+        let err = |what: &str| format!("line {}: bad {}", k + 1, what);
+        if f.len() != 9 {
+            return Err(format!(
+                "line {}: expected 9 fields, got {}",
+                k + 1,
+                f.len()
+            ));
+        }
+        results.push(RunResult {
+            test_id: f[0].parse().map_err(|_| err("test_id"))?,
+            instance: f[1].clone(),
+            algorithm: f[2].clone(),
+            params: f[3].clone(),
+            seed: match f[4].as_str() {
+                "" => None,
+                s => Some(s.parse().map_err(|_| err("seed"))?),
+            },
+            length: f[5].parse().map_err(|_| err("length"))?,
+            points: f[6].parse().map_err(|_| err("points"))?,
+            elapsed: f[7]
+                .parse()
+                .ok()
+                .and_then(|s| Duration::try_from_secs_f64(s).ok())
+                .ok_or_else(|| err("elapsed_s"))?,
+            tour: f[8]
+                .split_whitespace()
+                .map(|c| c.parse().map_err(|_| err("tour")))
+                .collect::<Result<_, _>>()?,
+        });
+    }
+    Ok(results)
+}
+
+// Минимальный разбор JSON: ровно то, что пишет `write_json` и совместимые инструменты
+#[derive(Debug)]
+enum Json {
+    Null,
+    // Исходная запись числа: зерно u64 не всегда точно представимо в f64
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+struct JsonParser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn skip_ws(&mut self) {
+        while self.pos < self.s.len() && self.s[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), String> {
+        self.skip_ws();
+        if self.s.get(self.pos) == Some(&b) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("expected '{}' at byte {}", b as char, self.pos))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_ws();
+        match self.s.get(self.pos) {
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                self.skip_ws();
+                if self.s.get(self.pos) == Some(&b'}') {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.skip_ws();
+                    let key = self.string()?;
+                    self.expect(b':')?;
+                    fields.push((key, self.value()?));
+                    self.skip_ws();
+                    match self.s.get(self.pos) {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(Json::Object(fields));
+                        }
+                        _ => return Err(format!("expected ',' or '}}' at byte {}", self.pos)),
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.skip_ws();
+                if self.s.get(self.pos) == Some(&b']') {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_ws();
+                    match self.s.get(self.pos) {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Json::Array(items));
+                        }
+                        _ => return Err(format!("expected ',' or ']' at byte {}", self.pos)),
+                    }
+                }
+            }
+            Some(b'"') => Ok(Json::String(self.string()?)),
+            Some(_) => {
+                let start = self.pos;
+                while self.pos < self.s.len()
+                    && !matches!(self.s[self.pos], b',' | b']' | b'}')
+                    && !self.s[self.pos].is_ascii_whitespace()
+                {
+                    self.pos += 1;
+                }
+                let token = std::str::from_utf8(&self.s[start..self.pos]).unwrap_or("");
+                match token {
+                    "null" => Ok(Json::Null),
+                    t if is_json_number(t) => Ok(Json::Number(t.to_string())),
+                    t => Err(format!("bad token '{}' at byte {}", t, start)),
+                }
+            }
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.s.get(self.pos) != Some(&b'"') {
+            return Err(format!("expected string at byte {}", self.pos));
+        }
+        self.pos += 1;
+        let mut bytes = Vec::new();
+        while let Some(&b) = self.s.get(self.pos) {
+            self.pos += 1;
+            match b {
+                b'"' => return String::from_utf8(bytes).map_err(|e| e.to_string()),
+                b'\\' => {
+                    let esc = *self.s.get(self.pos).ok_or("unterminated escape")?;
+                    self.pos += 1;
+                    let c = match esc {
+                        b'n' => '\n',
+                        b't' => '\t',
+                        b'r' => '\r',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'u' => {
+                            let hex = self
+                                .s
+                                .get(self.pos..self.pos + 4)
+                                .and_then(|h| std::str::from_utf8(h).ok())
+                                .and_then(|h| u32::from_str_radix(h, 16).ok())
+                                .ok_or("bad \\u escape")?;
+                            self.pos += 4;
+                            char::from_u32(hex).unwrap_or('\u{fffd}')
+                        }
+                        other => other as char,
+                    };
+                    let mut buf = [0u8; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                b => bytes.push(b),
+            }
+        }
+        Err("unterminated string".to_string())
+    }
+}
+
+// Грамматика числа JSON: `-?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?`; в отличие от
+// `f64::from_str` не пропускает `NaN`, `inf`, `+1`, `.5` и `1.`
+fn is_json_number(t: &str) -> bool {
+    let b = t.as_bytes();
+    let mut i = usize::from(b.first() == Some(&b'-'));
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while b.get(*i).is_some_and(u8::is_ascii_digit) {
+            *i += 1;
+        }
+        *i - start
+    };
+    match b.get(i) {
+        Some(b'0') => i += 1,
+        Some(b'1'..=b'9') => {
+            digits(&mut i);
+        }
+        _ => return false,
+    }
+    if b.get(i) == Some(&b'.') {
+        i += 1;
+        if digits(&mut i) == 0 {
+            return false;
+        }
+    }
+    if matches!(b.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(b.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        if digits(&mut i) == 0 {
+            return false;
+        }
+    }
+    i == b.len()
+}
+
+fn parse_json(content: &str) -> Result<Vec<RunResult>, String> {
+    let mut parser = JsonParser {
+        s: content.as_bytes(),
+        pos: 0,
+    };
+    let root = parser.value()?;
+    parser.skip_ws();
+    if parser.pos < parser.s.len() {
+        return Err(format!("trailing data at byte {}", parser.pos));
+    }
+    let items = match &root {
+        Json::Object(fields) => match fields.iter().find(|(k, _)| k == "results") {
+            Some((_, Json::Array(items))) => items,
+            _ => return Err("missing \"results\" array".to_string()),
+        },
+        Json::Array(items) => items,
+        _ => return Err("expected object or array".to_string()),
+    };
+
+    items
+        .iter()
+        .enumerate()
+        .map(|(k, item)| {
+            let Json::Object(fields) = item else {
+                return Err(format!("result {}: expected object", k));
+            };
+            let get = |key: &str| fields.iter().find(|(f, _)| f == key).map(|(_, v)| v);
+            let err = |key: &str| format!("result {}: missing or bad \"{}\"", k, key);
+            let number = |key: &str| match get(key) {
+                Some(Json::Number(v)) => v.parse::<f64>().map_err(|_| err(key)),
+                _ => Err(err(key)),
+            };
+            let index = |key: &str| match get(key) {
+                Some(Json::Number(v)) => v.parse::<usize>().map_err(|_| err(key)),
+                _ => Err(err(key)),
+            };
+            let string = |key: &str| match get(key) {
+                Some(Json::String(s)) => Ok(s.clone()),
+                None => Ok(String::new()),
+                _ => Err(err(key)),
+            };
+            Ok(RunResult {
+                test_id: index("test_id")?,
+                instance: string("instance")?,
+                algorithm: string("algorithm")?,
+                params: string("params")?,
+                seed: match get("seed") {
+                    None | Some(Json::Null) => None,
+                    Some(Json::Number(v)) => Some(v.parse().map_err(|_| err("seed"))?),
+                    _ => return Err(err("seed")),
+                },
+                length: number("length")?,
+                points: u32::try_from(index("points")?).map_err(|_| err("points"))?,
+                elapsed: Duration::try_from_secs_f64(number("elapsed_s").unwrap_or(0.0))
+                    .map_err(|_| err("elapsed_s"))?,
+                tour: match get("tour") {
+                    Some(Json::Array(cities)) => cities
+                        .iter()
+                        .map(|c| match c {
+                            Json::Number(v) => v.parse().map_err(|_| err("tour")),
+                            _ => Err(err("tour")),
+                        })
+                        .collect::<Result<_, _>>()?,
+                    _ => return Err(err("tour")),
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ResultSet {
+        let run = |test_id, seed| RunResult {
+            test_id,
+            instance: "data/tsp_5_1".to_string(),
+            algorithm: "acs".to_string(),
+            params: "alpha=1, \"q\"\t\\ ё".to_string(),
+            seed,
+            length: 1_234.567_890_123,
+            points: 7,
+            elapsed: Duration::from_micros(1_500_250),
+            tour: vec![0, 3, 1, 4, 2],
+        };
+        ResultSet {
+            results: vec![run(0, Some(u64::MAX)), run(1, None)],
+        }
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let set = sample();
+        let mut buf = Vec::new();
+        set.write_json(&mut buf).unwrap();
+        let parsed = parse_json(&String::from_utf8(buf).unwrap()).unwrap();
+        assert_eq!(parsed, set.results);
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let set = sample();
+        let mut buf = Vec::new();
+        set.write_csv(&mut buf).unwrap();
+        let parsed = parse_csv(&String::from_utf8(buf).unwrap()).unwrap();
+        assert_eq!(parsed, set.results);
+    }
+
+    #[test]
+    fn json_rejects_non_finite_numbers() {
+        let mut buf = Vec::new();
+        sample().write_json(&mut buf).unwrap();
+        let content = String::from_utf8(buf).unwrap();
+        assert!(content.contains("\"length\":1234.567890123,"));
+        for bad in ["NaN", "inf", "-inf", "+1", ".5", "1.", "1e"] {
+            let broken = content.replacen("1234.567890123", bad, 1);
+            assert!(parse_json(&broken).is_err(), "{} accepted", bad);
+        }
+        assert!(is_json_number("-0.5e+3"));
+    }
+
+    #[test]
+    fn json_rejects_trailing_data() {
+        let mut buf = Vec::new();
+        sample().write_json(&mut buf).unwrap();
+        let content = String::from_utf8(buf).unwrap();
+        assert!(parse_json(&format!("{}  \n", content)).is_ok());
+        let err = parse_json(&format!("{}{{}}", content)).unwrap_err();
+        assert!(err.contains("trailing data"), "{}", err);
+        assert!(parse_json(&content[..content.len() - 3]).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+use crate::tsp_data::TspData;
+use std::fmt;
+
+/// Эталонная длина экземпляра, относительно которой задаются полосы
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reference {
+    /// Известный оптимум
+    Optimum(f64),
+    /// Нижняя оценка (например, 1-tree или Held-Karp)
+    LowerBound(f64),
+}
+
+impl Reference {
+    pub fn length(&self) -> f64 {
+        match *self {
+            Reference::Optimum(l) | Reference::LowerBound(l) => l,
+        }
+    }
+}
+
+/// Нижняя оценка 1-tree: минимальное остовное дерево на вершинах без 0 плюс два
+/// кратчайших ребра из вершины 0. Prim по полному графу: O(n^2) времени, O(n) памяти
+pub fn one_tree_bound(data: &TspData) -> f64 {
+    let n = data.n;
+    if n < 3 {
+        return if n == 2 { 2.0 * data.dist(0, 1) } else { 0.0 };
+    }
+    let mut in_tree = vec![false; n];
+    let mut key = vec![f64::INFINITY; n];
+    key[1] = 0.0;
+    let mut total = 0.0;
+    for _ in 1..n {
+        let u = (1..n)
+            .filter(|&v| !in_tree[v])
+            .min_by(|&a, &b| key[a].total_cmp(&key[b]))
+            .unwrap();
+        in_tree[u] = true;
+        total += key[u];
+        for v in 1..n {
+            if !in_tree[v] {
+                key[v] = key[v].min(data.dist(u, v));
+            }
+        }
+    }
+    let (mut first, mut second) = (f64::INFINITY, f64::INFINITY);
+    for v in 1..n {
+        let d = data.dist(0, v);
+        if d < first {
+            second = first;
+            first = d;
+        } else if d < second {
+            second = d;
+        }
+    }
+    total + first + second
+}
+
+/// Граница полосы: тур попадает в полосу, если его длина не больше границы
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Threshold {
+    /// Абсолютная длина
+    Length(f64),
+    /// Отклонение от эталона: 0.05 -- не длиннее эталона более чем на 5%
+    Gap(f64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GradeBand {
+    pub label: String,
+    pub points: u32,
+    pub threshold: Threshold,
+}
+
+/// Шкала оценки экземпляра: набор полос, тур получает баллы лучшей из полос,
+/// в которые попадает; не попал ни в одну -- 0 баллов
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rubric {
+    pub reference: Option<Reference>,
+    pub bands: Vec<GradeBand>,
+}
+
+/// Оценка одного тура
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grade {
+    /// Полоса, `None` -- тур не попал ни в одну
+    pub band: Option<String>,
+    pub points: u32,
+    /// Максимум баллов по шкале
+    pub max_points: u32,
+    /// Отклонение от эталона (0.05 -- +5%), если эталон задан
+    pub gap: Option<f64>,
+}
+
+impl Rubric {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_reference(mut self, reference: Reference) -> Self {
+        self.reference = Some(reference);
+        self
+    }
+
+    pub fn band(mut self, label: &str, points: u32, threshold: Threshold) -> Self {
+        self.bands.push(GradeBand {
+            label: label.to_string(),
+            points,
+            threshold,
+        });
+        self
+    }
+
+    /// Граница полосы в единицах длины; `None` для `Gap` без эталона
+    pub fn limit(&self, threshold: Threshold) -> Option<f64> {
+        match threshold {
+            Threshold::Length(l) => Some(l),
+            Threshold::Gap(g) => self.reference.map(|r| r.length() * (1.0 + g)),
+        }
+    }
+
+    pub fn max_points(&self) -> u32 {
+        self.bands.iter().map(|b| b.points).max().unwrap_or(0)
+    }
+
+    pub fn grade(&self, length: f64) -> Grade {
+        let band = self
+            .bands
+            .iter()
+            .filter(|b| self.limit(b.threshold).is_some_and(|limit| length <= limit))
+            .max_by_key(|b| b.points);
+        Grade {
+            band: band.map(|b| b.label.clone()),
+            points: band.map_or(0, |b| b.points),
+            max_points: self.max_points(),
+            gap: self
+                .reference
+                .map(|r| length / r.length().max(f64::MIN_POSITIVE) - 1.0),
+        }
+    }
+}
+
+/// Строка итоговой таблицы: оцененный тур или причина, по которой оценки нет
+#[derive(Clone, Debug)]
+pub struct ScoreRow {
+    pub test_id: usize,
+    pub instance: String,
+    pub algorithm: String,
+    pub length: Option<f64>,
+    pub grade: Option<Grade>,
+    /// Максимум баллов по шкале теста; учитывается и для тестов без оценки
+    pub max_points: u32,
+    /// Ошибка или пропуск теста
+    pub note: String,
+}
+
+/// Итоговая таблица баллов по тестам и алгоритмам
+#[derive(Clone, Debug, Default)]
+pub struct ScoreTable {
+    pub rows: Vec<ScoreRow>,
+}
+
+impl ScoreTable {
+    pub fn total_points(&self) -> u32 {
+        self.rows
+            .iter()
+            .filter_map(|r| r.grade.as_ref())
+            .map(|g| g.points)
+            .sum()
+    }
+
+    pub fn max_points(&self) -> u32 {
+        self.rows.iter().map(|r| r.max_points).sum()
+    }
+}
+
+impl fmt::Display for ScoreTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let header = [
+            "test",
+            "instance",
+            "algorithm",
+            "length",
+            "gap",
+            "band",
+            "points",
+        ];
+        let cells: Vec<[String; 7]> = self
+            .rows
+            .iter()
+            .map(|r| {
+                let grade = r.grade.as_ref();
+                [
+                    r.test_id.to_string(),
+                    r.instance.clone(),
+                    r.algorithm.clone(),
+                    r.length.map_or_else(|| "-".into(), |l| format!("{:.2}", l)),
+                    grade
+                        .and_then(|g| g.gap)
+                        .map_or_else(|| "-".into(), |g| format!("{:+.2}%", g * 100.0)),
+                    match grade {
+                        Some(g) => g.band.clone().unwrap_or_else(|| "-".into()),
+                        None => r.note.clone(),
+                    },
+                    format!("{}/{}", grade.map_or(0, |g| g.points), r.max_points),
+                ]
+            })
+            .collect();
+
+        let mut widths = header.map(str::len);
+        for row in &cells {
+            for (w, cell) in widths.iter_mut().zip(row) {
+                *w = (*w).max(cell.chars().count());
+            }
+        }
+        // Числовые колонки выравниваются вправо
+        let numeric = [true, false, false, true, true, false, true];
+        let line = |f: &mut fmt::Formatter, row: &[String]| -> fmt::Result {
+            for (k, cell) in row.iter().enumerate() {
+                if k > 0 {
+                    write!(f, "  ")?;
+                }
+                if numeric[k] {
+                    write!(f, "{:>w$}", cell, w = widths[k])?;
+                } else {
+                    write!(f, "{:<w$}", cell, w = widths[k])?;
+                }
+            }
+            writeln!(f)
+        };
+
+        line(f, &header.map(String::from))?;
+        let total_width = widths.iter().sum::<usize>() + 2 * (widths.len() - 1);
+        writeln!(f, "{}", "-".repeat(total_width))?;
+        for row in &cells {
+            line(f, row)?;
+        }
+        writeln!(f, "{}", "-".repeat(total_width))?;
+        write!(
+            f,
+            "Total: {} / {} points",
+            self.total_points(),
+            self.max_points()
+        )
+    }
+}
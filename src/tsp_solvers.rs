@@ -1,37 +1,208 @@
-use crate::tsp_data::TspData;
+use crate::tsp_constructors::{
+    Construction, build_tour, greedy_initial_tour, nearest_neighbour_tour,
+};
+use crate::tsp_data::{TspData, validate_permutation};
+use crate::tsp_observers::{IterationStats, LogObserver, SolverObserver};
 use rand::distr::{Distribution, Uniform};
 use rand::prelude::*;
-use std::time::Instant;
+use rand_chacha::ChaCha8Rng;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
 type PheromoneType = f32;
 
+/// Общий интерфейс солверов, чтобы тестовый стенд мог гонять их одинаково
+pub trait TspSolver {
+    fn name(&self) -> &'static str;
+    fn run(&mut self);
+    fn best_tour(&self) -> &[usize];
+    fn best_score(&self) -> f64;
+    fn history(&self) -> &[f64];
+    /// Флаг, по которому главный цикл досрочно завершается с лучшим найденным туром
+    fn set_cancel_token(&mut self, token: CancelToken);
+    /// Замена наблюдателя (по умолчанию -- `LogObserver`)
+    fn set_observer(&mut self, observer: Box<dyn SolverObserver>);
+    /// Зерно генератора, если оно задано в конфигурации
+    fn seed(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Флаг отмены, общий для солвера и обработчика Ctrl-C
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// Генератор солвера: по зерну -- воспроизводимый прогон, без зерна -- случайный
+fn seeded_rng(seed: Option<u64>) -> ChaCha8Rng {
+    match seed {
+        Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+        None => ChaCha8Rng::from_rng(&mut rand::rng()),
+    }
+}
+
+// Реализация `TspSolver` через поля результата и собственный `run`
+macro_rules! impl_tsp_solver {
+    ($solver:ty, $name:expr, $tour:ident, $score:ident $(, $seed:ident)?) => {
+        impl TspSolver for $solver {
+            fn name(&self) -> &'static str {
+                $name
+            }
+            fn run(&mut self) {
+                <$solver>::run(self)
+            }
+            fn best_tour(&self) -> &[usize] {
+                &self.$tour
+            }
+            fn best_score(&self) -> f64 {
+                self.$score
+            }
+            fn history(&self) -> &[f64] {
+                &self.history
+            }
+            fn set_cancel_token(&mut self, token: CancelToken) {
+                self.cancel = token;
+            }
+            fn set_observer(&mut self, observer: Box<dyn SolverObserver>) {
+                self.observer = observer;
+            }
+            $(
+                fn seed(&self) -> Option<u64> {
+                    self.config.$seed
+                }
+            )?
+        }
+    };
+}
+
+impl_tsp_solver!(AcsTspSolver, "ACS", best_tour, best_score, seed);
+impl_tsp_solver!(MmasTspSolver, "MMAS", best_tour, best_score, seed);
+impl_tsp_solver!(PsoTspSolver, "PSO", gbest_tour, gbest_score, seed);
+impl_tsp_solver!(
+    DiscretePsoTspSolver,
+    "Discrete PSO",
+    gbest_tour,
+    gbest_score,
+    seed
+);
+impl_tsp_solver!(GaTspSolver, "GA", best_tour, best_score, seed);
+impl_tsp_solver!(SaTspSolver, "SA", best_tour, best_score, seed);
+impl_tsp_solver!(IlsTspSolver, "ILS", best_tour, best_score, seed);
+impl_tsp_solver!(AbcTspSolver, "ABC", best_tour, best_score, seed);
+impl_tsp_solver!(FireflyTspSolver, "Firefly", best_tour, best_score, seed);
+impl_tsp_solver!(ConstructionTspSolver, "Construction", best_tour, best_score);
+
 pub struct AcsTspSolver {
     data: TspData,
-    n_ants: usize,
-    n_iterations: usize,
-    q0: f64,
-    beta: f64,
-    rho: f64,
-    phi: f64,
+    config: AcsConfig,
 
     pheromone: Vec<PheromoneType>,
     candidates: Vec<Vec<usize>>,
 
     tau0: f64,
 
+    // Собственный генератор и счетчик итераций -- часть сохраняемого состояния
+    rng: ChaCha8Rng,
+    iteration: usize,
+
     pub best_tour: Vec<usize>,
     pub best_score: f64,
     pub history: Vec<f64>,
+
+    cancel: CancelToken,
+    observer: Box<dyn SolverObserver>,
+}
+
+/// Правило глобального обновления феромона в муравьином солвере
+#[derive(Clone, Copy, Debug)]
+pub enum UpdateRule {
+    /// Классический ACS: испарение и отложение только на глобальном лучшем,
+    /// плюс локальное обновление во время построения
+    Acs,
+    /// Elitist AS: испарение везде, откладывают все муравьи,
+    /// глобальный лучший получает дополнительный вес `weight`
+    Elitist { weight: f64 },
+    /// Rank-based AS: откладывают `w - 1` лучших муравьев итерации с весом `w - r`,
+    /// глобальный лучший -- с весом `w`
+    RankBased { w: usize },
+}
+
+#[derive(Clone, Debug)]
+pub struct AcsConfig {
+    pub n_ants: usize,
+    pub n_iterations: usize,
+    pub q0: f64,
+    /// Показатель степени феромона в правиле выбора
+    pub alpha: f64,
+    pub beta: f64,
+    /// Коэффициент глобального испарения
+    pub rho: f64,
+    /// Коэффициент локального обновления
+    pub phi: f64,
+    pub update_rule: UpdateRule,
+    /// Какой тур откладывает феромон: лучший в итерации, глобальный лучший или по расписанию
+    pub deposit: DepositPolicy,
+    /// Эвристика стартового тура (задает best_tour и tau0)
+    pub construction: Construction,
+    /// Число случайных стартовых вершин для параллельного NN + 2-opt/Or-opt,
+    /// 0 -- только `construction`
+    pub multi_start: usize,
+    /// Зерно генератора муравьев, `None` -- случайное
+    pub seed: Option<u64>,
+    pub checkpoint: Option<Checkpointing>,
+}
+
+/// Периодическое сохранение состояния ACS на диск
+#[derive(Clone, Debug)]
+pub struct Checkpointing {
+    pub path: PathBuf,
+    /// Сохранять после каждой `every`-й итерации
+    pub every: usize,
+}
+
+impl Default for AcsConfig {
+    fn default() -> Self {
+        AcsConfig {
+            n_ants: 32,
+            n_iterations: 256,
+            q0: 0.9,
+            alpha: 1.0,
+            beta: 2.0,
+            rho: 0.1,
+            phi: 0.1,
+            update_rule: UpdateRule::Acs,
+            deposit: DepositPolicy::GlobalBest,
+            construction: Construction::NearestNeighbour,
+            multi_start: 0,
+            seed: None,
+            checkpoint: None,
+        }
+    }
 }
 
 pub struct PsoTspSolver {
     data: TspData,
-    num_particles: usize,
-    iterations: usize,
-
-    w: f64,
-    c1: f64,
-    c2: f64,
+    config: PsoConfig,
+    // Нужны только для Or-opt в гибридном режиме
+    candidates: Vec<Vec<usize>>,
 
     positions: Vec<Vec<f64>>,
     velocities: Vec<Vec<f64>>,
@@ -44,533 +215,3319 @@ pub struct PsoTspSolver {
     pub gbest_score: f64,
 
     pub history: Vec<f64>,
-}
 
-impl AcsTspSolver {
-    pub fn new(data: TspData, n_ants: usize, n_iterations: usize, q0: f64, beta: f64) -> Self {
-        let n = data.n;
+    rng: ChaCha8Rng,
+    cancel: CancelToken,
+    observer: Box<dyn SolverObserver>,
+}
 
-        // Кандидаты: 25 для маленьких, 40 для больших графов
-        // Для универсальности берем 30
-        println!("Precomputing Candidate Lists (Top 30)...");
-        let k_candidates = 30;
-        let mut candidates = vec![Vec::new(); n];
+/// Расписание инерционного веса w
+#[derive(Clone, Copy, Debug)]
+pub enum InertiaSchedule {
+    Constant(f64),
+    /// Линейное уменьшение от `start` на первой итерации до `end` на последней
+    LinearDecreasing {
+        start: f64,
+        end: f64,
+    },
+}
 
-        for i in 0..n {
-            let mut dists: Vec<(usize, f64)> = (0..n)
-                .filter(|&j| i != j)
-                .map(|j| (j, data.dist(i, j)))
-                .collect();
-            dists.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-            candidates[i] = dists.iter().take(k_candidates).map(|x| x.0).collect();
+impl InertiaSchedule {
+    fn at(&self, it: usize, iterations: usize) -> f64 {
+        match *self {
+            InertiaSchedule::Constant(w) => w,
+            InertiaSchedule::LinearDecreasing { start, end } => {
+                let t = it as f64 / (iterations.max(2) - 1) as f64;
+                start + (end - start) * t
+            }
         }
+    }
+}
 
-        // --- УЛУЧШЕННЫЙ СТАРТ ---
-        // Сразу оптимизируем жадный путь, чтобы задать высокую планку tau0
-        let mut greedy_tour = Self::greedy_initial_tour(&data, &candidates);
-        // Применяем 2-opt к жадному старту
-        Self::static_two_opt(&data, &mut greedy_tour);
-
-        let greedy_len = data.calculate_tour_length(&greedy_tour);
-        // Формула ACS
-        let tau0 = 1.0 / (n as f64 * greedy_len);
-
-        println!("Optimized Baseline: {:.2}, Tau0: {:.6e}", greedy_len, tau0);
+/// Топология соседства: у кого частица берет социальный компонент
+#[derive(Clone, Copy, Debug)]
+pub enum Topology {
+    /// Глобальный лучший (gbest)
+    Global,
+    /// Кольцо: соседи i-1 и i+1
+    Ring,
+    /// Тор-решетка фон Неймана: соседи сверху, снизу, слева, справа
+    VonNeumann,
+}
 
-        let pheromone = vec![tau0 as PheromoneType; n * n];
+#[derive(Clone, Debug)]
+pub struct PsoConfig {
+    pub num_particles: usize,
+    pub iterations: usize,
+    pub inertia: InertiaSchedule,
+    pub c1: f64,
+    pub c2: f64,
+    /// Ограничение компонент скорости по модулю, `None` -- без ограничения
+    pub v_max: Option<f64>,
+    pub topology: Topology,
+    /// Гибридный режим: 2-opt + Or-opt для pbest туров с записью результата обратно в ключи
+    pub local_search: bool,
+    /// Зерно генератора роя, `None` -- случайное
+    pub seed: Option<u64>,
+}
 
-        AcsTspSolver {
-            data,
-            n_ants,
-            n_iterations,
-            q0,
-            beta,
-            rho: 0.1,
-            phi: 0.1,
-            pheromone,
-            candidates,
-            tau0,
-            best_tour: greedy_tour,
-            best_score: greedy_len,
-            history: Vec::new(),
+impl Default for PsoConfig {
+    fn default() -> Self {
+        PsoConfig {
+            num_particles: 128,
+            iterations: 512,
+            inertia: InertiaSchedule::Constant(0.7),
+            c1: 1.5,
+            c2: 1.5,
+            v_max: None,
+            topology: Topology::Global,
+            local_search: false,
+            seed: None,
         }
     }
+}
 
-    // Статический метод для оптимизации при инициализации
-    fn static_two_opt(data: &TspData, tour: &mut Vec<usize>) {
-        let n = tour.len();
-        let mut improved = true;
-        let mut passes = 0;
-        while improved && passes < 5 {
-            improved = false;
-            passes += 1;
-            for i in 1..(n - 1) {
-                let limit = std::cmp::min(n, i + 200);
-                for j in (i + 1)..limit {
-                    if j - i == 1 {
-                        continue;
-                    }
-                    let u = tour[i - 1];
-                    let v = tour[i];
-                    let w = tour[j];
-                    let z = tour[(j + 1) % n];
-                    if data.dist(u, w) + data.dist(v, z) < data.dist(u, v) + data.dist(w, z) - 1e-8
-                    {
-                        tour[i..=j].reverse();
-                        improved = true;
-                    }
-                }
-            }
+impl PsoConfig {
+    /// Гибридный PSO: ограничение скорости, убывающая инерция, кольцо и локальный поиск
+    pub fn hybrid() -> Self {
+        PsoConfig {
+            inertia: InertiaSchedule::LinearDecreasing {
+                start: 0.9,
+                end: 0.4,
+            },
+            v_max: Some(0.1),
+            topology: Topology::Ring,
+            local_search: true,
+            ..PsoConfig::default()
         }
     }
+}
 
-    fn greedy_initial_tour(data: &TspData, candidates: &[Vec<usize>]) -> Vec<usize> {
-        let n = data.n;
-        let mut unvisited = vec![true; n];
-        let mut tour = Vec::with_capacity(n);
-        let mut curr = 0;
-        tour.push(0);
-        unvisited[0] = false;
-
-        for _ in 1..n {
-            let mut next_node = 0;
-            let mut found = false;
-            for &cand in &candidates[curr] {
-                if unvisited[cand] {
-                    next_node = cand;
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
-                let mut best_dist = f64::INFINITY;
-                for j in 0..n {
-                    if unvisited[j] {
-                        let d = data.dist(curr, j);
-                        if d < best_dist {
-                            best_dist = d;
-                            next_node = j;
-                        }
-                    }
-                }
+/// Какой тур откладывает феромон при глобальном обновлении
+#[derive(Clone, Copy, Debug)]
+pub enum DepositPolicy {
+    /// Лучший муравей текущей итерации
+    IterationBest,
+    /// Лучший тур за все время
+    GlobalBest,
+    /// Каждую `period`-ю итерацию -- глобальный лучший, иначе лучший в итерации
+    Alternating(usize),
+    /// Расписание Штютцле-Хоса: global-best используется тем чаще,
+    /// чем больше итераций прошло с последнего рестарта
+    Schedule,
+}
+
+impl DepositPolicy {
+    fn use_global_best(&self, it: usize) -> bool {
+        match *self {
+            DepositPolicy::IterationBest => false,
+            DepositPolicy::GlobalBest => true,
+            DepositPolicy::Alternating(period) => period > 0 && it % period == period - 1,
+            DepositPolicy::Schedule => {
+                let freq = match it {
+                    0..25 => return false,
+                    25..75 => 5,
+                    75..125 => 3,
+                    125..250 => 2,
+                    _ => 1,
+                };
+                it.is_multiple_of(freq)
             }
-            curr = next_node;
-            tour.push(curr);
-            unvisited[curr] = false;
         }
-        tour
     }
+}
 
-    // Быстрый 2-opt (Windowed)
-    fn two_opt_fast(&self, tour: &mut Vec<usize>) {
-        let n = tour.len();
-        let mut improved = true;
-        let mut passes = 0;
-
-        // Лимит 8 проходов - баланс скорости и качества
-        while improved && passes < 8 {
-            improved = false;
-            passes += 1;
-
-            for i in 1..(n - 1) {
-                // Окно 200 для скорости на больших графах
-                let limit = std::cmp::min(n, i + 200);
-                for j in (i + 1)..limit {
-                    if j - i == 1 {
-                        continue;
-                    }
-                    let u = tour[i - 1];
-                    let v = tour[i];
-                    let w = tour[j];
-                    let z = tour[(j + 1) % n];
+#[derive(Clone, Debug)]
+pub struct MmasConfig {
+    pub n_ants: usize,
+    pub n_iterations: usize,
+    pub alpha: f64,
+    pub beta: f64,
+    pub rho: f64,
+    /// Вероятность построить лучший тур при полной сходимости, задает tau_min
+    pub p_best: f64,
+    pub deposit: DepositPolicy,
+    /// Сглаживание феромона (PTS) при стагнации: tau += delta * (tau_max - tau)
+    pub smoothing: f64,
+    /// Сколько итераций без улучшения считаем стагнацией
+    pub stagnation_limit: usize,
+    /// Зерно генератора муравьев, `None` -- случайное
+    pub seed: Option<u64>,
+}
 
-                    if self.data.dist(u, w) + self.data.dist(v, z)
-                        < self.data.dist(u, v) + self.data.dist(w, z) - 1e-8
-                    {
-                        tour[i..=j].reverse();
-                        improved = true;
-                    }
-                }
-            }
+impl Default for MmasConfig {
+    fn default() -> Self {
+        MmasConfig {
+            n_ants: 32,
+            n_iterations: 256,
+            alpha: 1.0,
+            beta: 2.0,
+            rho: 0.02,
+            p_best: 0.05,
+            deposit: DepositPolicy::Schedule,
+            smoothing: 0.5,
+            stagnation_limit: 50,
+            seed: None,
         }
     }
+}
 
-    // Or-opt (Heavy)
-    fn or_opt(&self, tour: &mut Vec<usize>) -> bool {
-        let n = tour.len();
-        let mut improved = false;
-        let mut pos = vec![0; n];
-        for (i, &node) in tour.iter().enumerate() {
-            pos[node] = i;
-        }
-
-        for &block_size in &[3, 2, 1] {
-            let mut continue_search = true;
-            let mut moves_limit = 0;
+pub struct MmasTspSolver {
+    data: TspData,
+    config: MmasConfig,
 
-            // Разрешаем не более 10 перестановок за вызов, чтобы не виснуть
-            while continue_search && moves_limit < 10 {
-                continue_search = false;
+    pheromone: Vec<PheromoneType>,
+    candidates: Vec<Vec<usize>>,
 
-                for i in 0..n {
-                    if i + block_size >= n - 1 {
-                        continue;
-                    }
+    tau_min: f64,
+    tau_max: f64,
 
-                    let start_node = tour[i];
-                    let end_node = tour[i + block_size - 1];
-                    let prev_node = tour[if i == 0 { n - 1 } else { i - 1 }];
-                    let next_node = tour[i + block_size];
-
-                    let cost_rem =
-                        self.data.dist(prev_node, start_node) + self.data.dist(end_node, next_node);
-                    let cost_add = self.data.dist(prev_node, next_node);
-                    let reduction = cost_rem - cost_add;
-
-                    // Ищем куда вставить, проверяя кандидатов start_node
-                    for &target_node in &self.candidates[start_node] {
-                        let target_idx = pos[target_node];
-                        if target_idx >= (if i == 0 { n - 1 } else { i - 1 })
-                            && target_idx <= i + block_size
-                        {
-                            continue;
-                        }
+    // Лучший тур с последнего рестарта
+    restart_best_tour: Vec<usize>,
+    restart_best_score: f64,
 
-                        let target_next_idx = (target_idx + 1) % n;
-                        let target_next_node = tour[target_next_idx];
+    pub best_tour: Vec<usize>,
+    pub best_score: f64,
+    pub history: Vec<f64>,
 
-                        let cost_ins = self.data.dist(target_node, start_node)
-                            + self.data.dist(end_node, target_next_node);
-                        let cost_break = self.data.dist(target_node, target_next_node);
+    rng: ChaCha8Rng,
+    cancel: CancelToken,
+    observer: Box<dyn SolverObserver>,
+}
 
-                        if reduction > (cost_ins - cost_break) + 1e-6 {
-                            // Move block
-                            let block: Vec<usize> = tour.drain(i..i + block_size).collect();
-                            let mut new_target_idx = target_idx;
-                            if target_idx > i {
-                                new_target_idx -= block_size;
-                            }
+/// Тип элементарного хода, из которых состоит скорость дискретного PSO
+#[derive(Clone, Copy, Debug)]
+pub enum MoveKind {
+    /// Обмен двух городов местами
+    Swap,
+    /// Разворот отрезка тура (2-opt ход)
+    TwoOpt,
+}
 
-                            let insert_at = (new_target_idx + 1) % tour.len();
-                            for (k, &node) in block.iter().enumerate() {
-                                tour.insert(insert_at + k, node);
-                            }
+// Ходы задаются городами, а не позициями: так подмножество разности
+// остается осмысленным, даже если часть ходов отброшена
+#[derive(Clone, Copy, Debug)]
+enum Move {
+    /// Поменять местами города `a` и `b`
+    Swap(usize, usize),
+    /// 2-opt разворот, после которого `b` становится соседом `a`
+    Link(usize, usize),
+}
 
-                            // Rebuild cache
-                            for (idx, &val) in tour.iter().enumerate() {
-                                pos[val] = idx;
-                            }
+#[derive(Clone, Debug)]
+pub struct DiscretePsoConfig {
+    pub num_particles: usize,
+    pub iterations: usize,
+    /// Вероятность сохранить каждый ход прошлой скорости
+    pub w: f64,
+    /// c1 * r1 и c2 * r2 трактуются как вероятности взять ход из разности с pbest/gbest
+    pub c1: f64,
+    pub c2: f64,
+    pub moves: MoveKind,
+    /// Максимальная длина скорости в ходах, 0 -- без ограничения
+    pub max_velocity: usize,
+    /// Ограничение времени работы: разность туров строится за O(n^2),
+    /// на больших экземплярах итерация занимает секунды
+    pub time_limit: Option<Duration>,
+    /// Зерно генератора роя, `None` -- случайное
+    pub seed: Option<u64>,
+}
 
-                            improved = true;
-                            continue_search = true;
-                            moves_limit += 1;
-                            break;
-                        }
-                    }
-                    if continue_search {
-                        break;
-                    }
-                }
-            }
+impl Default for DiscretePsoConfig {
+    fn default() -> Self {
+        DiscretePsoConfig {
+            num_particles: 128,
+            iterations: 512,
+            w: 0.5,
+            c1: 0.5,
+            c2: 0.5,
+            moves: MoveKind::TwoOpt,
+            max_velocity: 0,
+            time_limit: None,
+            seed: None,
         }
-        improved
     }
+}
 
-    fn select_next_city(&self, curr: usize, unvisited_mask: &[bool], rng: &mut ThreadRng) -> usize {
-        // Fast candidate selection
-        let mut candidates_vec: Vec<usize> = Vec::with_capacity(30);
-        for &c in &self.candidates[curr] {
-            if unvisited_mask[c] {
-                candidates_vec.push(c);
-            }
-        }
+/// PSO над перестановками: позиция -- тур, скорость -- последовательность ходов
+pub struct DiscretePsoTspSolver {
+    data: TspData,
+    config: DiscretePsoConfig,
 
-        if !candidates_vec.is_empty() {
-            if rng.random_range(0.0..1.0) <= self.q0 {
-                let mut best_node = candidates_vec[0];
-                let mut best_val = -1.0;
-                for &node in &candidates_vec {
-                    // Precompute distance? No, fast enough.
-                    let val = self.pheromone[curr * self.data.n + node]
-                        * (1.0 / self.data.dist(curr, node)).powf(self.beta) as PheromoneType;
-                    if val > best_val {
-                        best_val = val;
-                        best_node = node;
-                    }
-                }
-                return best_node;
-            } else {
-                let mut values = Vec::with_capacity(candidates_vec.len());
-                let mut sum = 0.0;
-                for &node in &candidates_vec {
-                    let val = self.pheromone[curr * self.data.n + node]
-                        * (1.0 / self.data.dist(curr, node)).powf(self.beta) as PheromoneType;
-                    values.push(val);
-                    sum += val;
-                }
-                if sum == 0.0 {
-                    return candidates_vec[0];
-                }
-                let r = rng.random_range(0.0..1.0) * sum;
-                let mut acc = 0.0;
-                for (i, &v) in values.iter().enumerate() {
-                    acc += v;
-                    if acc >= r {
-                        return candidates_vec[i];
-                    }
-                }
-                return *candidates_vec.last().unwrap();
-            }
-        }
+    positions: Vec<Vec<usize>>,
+    velocities: Vec<Vec<Move>>,
 
-        // Fallback
-        for i in 0..self.data.n {
-            if unvisited_mask[i] {
-                return i;
-            }
-        }
-        0
-    }
+    pbest_tours: Vec<Vec<usize>>,
+    pbest_scores: Vec<f64>,
 
-    fn local_update(&mut self, u: usize, v: usize) {
-        let idx = u * self.data.n + v;
-        let idx2 = v * self.data.n + u;
-        self.pheromone[idx] = (1.0 - self.phi) as PheromoneType * self.pheromone[idx]
-            + (self.phi * self.tau0) as PheromoneType;
-        self.pheromone[idx2] = self.pheromone[idx];
-    }
+    pub gbest_tour: Vec<usize>,
+    pub gbest_score: f64,
 
-    fn global_update(&mut self) {
-        let deposit = 1.0 / self.best_score;
-        let n = self.data.n;
-        for i in 0..n {
-            let u = self.best_tour[i];
-            let v = self.best_tour[(i + 1) % n];
-            let idx = u * n + v;
-            let idx2 = v * n + u;
-            self.pheromone[idx] = (1.0 - self.rho) as PheromoneType * self.pheromone[idx]
-                + (self.rho * deposit) as PheromoneType;
-            self.pheromone[idx2] = self.pheromone[idx];
-        }
-    }
+    pub history: Vec<f64>,
 
-    pub fn run(&mut self) {
-        println!(
-            "Starting Robust ACS (beta={}, ants={})...",
-            self.beta, self.n_ants
-        );
-        let start = Instant::now();
-        let mut rng = rand::rng();
-        let n = self.data.n;
+    rng: ChaCha8Rng,
+    cancel: CancelToken,
+    observer: Box<dyn SolverObserver>,
+}
 
-        for it in 0..self.n_iterations {
-            // Храним лучшего муравья этой итерации
-            let mut iter_best_score = f64::INFINITY;
-            let mut iter_best_tour = Vec::new();
+/// Оператор скрещивания генетического алгоритма
+#[derive(Clone, Copy, Debug)]
+pub enum Crossover {
+    /// Order Crossover (OX)
+    Order,
+    /// Partially Mapped Crossover (PMX)
+    Pmx,
+    /// Edge Recombination Crossover (ERX)
+    EdgeRecombination,
+    /// Edge Assembly Crossover (EAX): одна AB-цепь + слияние подтуров
+    Eax,
+}
 
-            for _k in 0..self.n_ants {
-                let mut tour = Vec::with_capacity(n);
-                let start_node = rng.random_range(0..n);
-                tour.push(start_node);
+#[derive(Clone, Debug)]
+pub struct GaConfig {
+    pub population: usize,
+    pub generations: usize,
+    pub crossover: Crossover,
+    /// Вероятность мутации потомка (разворот случайного отрезка)
+    pub mutation_rate: f64,
+    /// Размер турнира при выборе родителей
+    pub tournament: usize,
+    /// Сколько лучших особей переходят в следующее поколение без изменений
+    pub elitism: usize,
+    /// Доводить потомков 2-opt + Or-opt
+    pub local_search: bool,
+    /// Зерно генератора популяции, `None` -- случайное
+    pub seed: Option<u64>,
+}
 
-                let mut mask = vec![true; n];
-                mask[start_node] = false;
-                let mut count = 1;
-                let mut curr = start_node;
+impl Default for GaConfig {
+    fn default() -> Self {
+        GaConfig {
+            population: 64,
+            generations: 256,
+            crossover: Crossover::Eax,
+            mutation_rate: 0.1,
+            tournament: 3,
+            elitism: 2,
+            local_search: true,
+            seed: None,
+        }
+    }
+}
 
-                while count < n {
-                    let next = self.select_next_city(curr, &mask, &mut rng);
-                    mask[next] = false;
-                    self.local_update(curr, next);
-                    tour.push(next);
-                    curr = next;
-                    count += 1;
-                }
-                self.local_update(tour[n - 1], tour[0]);
+pub struct GaTspSolver {
+    data: TspData,
+    config: GaConfig,
+    candidates: Vec<Vec<usize>>,
 
-                // 1. Все делают быстрый 2-opt
-                self.two_opt_fast(&mut tour);
-                let score = self.data.calculate_tour_length(&tour);
+    // (длина, тур), отсортировано по возрастанию длины
+    population: Vec<(f64, Vec<usize>)>,
 
-                // Запоминаем лучшего в итерации
-                if score < iter_best_score {
-                    iter_best_score = score;
-                    iter_best_tour = tour;
-                }
-            }
+    pub best_tour: Vec<usize>,
+    pub best_score: f64,
+    pub history: Vec<f64>,
 
-            // 2. СТРАТЕГИЯ "ЧЕМПИОН ИТЕРАЦИИ"
-            // Только победитель гонки получает Or-opt.
-            // Это гарантирует, что мы всегда пытаемся улучшить лучший результат,
-            // но не тратим время на остальных.
-            if self.or_opt(&mut iter_best_tour) {
-                // Если улучшили, пересчитываем скор
-                // iter_best_score = self.data.calculate_tour_length(&iter_best_tour);
-                // И полируем еще раз 2-opt'ом
-                self.two_opt_fast(&mut iter_best_tour);
-                iter_best_score = self.data.calculate_tour_length(&iter_best_tour);
-            }
+    rng: ChaCha8Rng,
+    cancel: CancelToken,
+    observer: Box<dyn SolverObserver>,
+}
 
-            // Обновляем глобальный рекорд
-            if iter_best_score < self.best_score {
-                self.best_score = iter_best_score;
-                self.best_tour = iter_best_tour;
-                println!("Iter {}: NEW RECORD {:.2}", it, self.best_score);
-            }
+/// Схема охлаждения имитации отжига
+#[derive(Clone, Copy, Debug)]
+pub enum Cooling {
+    /// T <- alpha * T после каждой ступени
+    Geometric { alpha: f64 },
+    /// Температура подстраивается под целевую долю принятых ходов,
+    /// которая линейно убывает от `target_acceptance` до нуля
+    Adaptive { target_acceptance: f64 },
+    /// Геометрическое охлаждение с подогревом до `reheat * T0`,
+    /// если `patience` ступеней нет улучшения и система уже остыла ниже этого уровня
+    Reheating {
+        alpha: f64,
+        patience: usize,
+        reheat: f64,
+    },
+}
 
-            // Глобальное обновление феромонов по глобальному лучшему
-            self.global_update();
-            self.history.push(self.best_score);
+#[derive(Clone, Debug)]
+pub struct SaConfig {
+    /// Число температурных ступеней
+    pub iterations: usize,
+    /// Число пробных ходов на одной ступени
+    pub moves_per_temp: usize,
+    /// Начальная температура, `None` -- оценить по случайным ходам
+    pub initial_temp: Option<f64>,
+    pub cooling: Cooling,
+    /// Доля Or-opt ходов, остальные -- 2-opt
+    pub or_opt_prob: f64,
+    /// Зерно генератора ходов, `None` -- случайное
+    pub seed: Option<u64>,
+}
+
+impl Default for SaConfig {
+    fn default() -> Self {
+        SaConfig {
+            iterations: 1000,
+            moves_per_temp: 5000,
+            initial_temp: None,
+            cooling: Cooling::Geometric { alpha: 0.995 },
+            or_opt_prob: 0.3,
+            seed: None,
         }
-        println!(
-            "Done in {:.2?}s. Best: {:.2}",
-            start.elapsed(),
-            self.best_score
-        );
     }
 }
 
-impl PsoTspSolver {
-    pub fn new(
-        data: TspData,
-        num_particles: usize,
-        iterations: usize,
-        w: f64,
+pub struct SaTspSolver {
+    data: TspData,
+    config: SaConfig,
+    candidates: Vec<Vec<usize>>,
+
+    tour: Vec<usize>,
+    // Обратная перестановка: позиция города в `tour`
+    pos: Vec<usize>,
+    score: f64,
+
+    pub best_tour: Vec<usize>,
+    pub best_score: f64,
+    pub history: Vec<f64>,
+
+    rng: ChaCha8Rng,
+    cancel: CancelToken,
+    observer: Box<dyn SolverObserver>,
+}
+
+/// Возмущение (kick) итерированного локального поиска
+#[derive(Clone, Copy, Debug)]
+pub enum Kick {
+    /// Double-bridge: A B C D -> A C B D на коротких соседних отрезках
+    DoubleBridge,
+    /// Несколько случайных разворотов отрезков
+    SegmentReversal { segments: usize },
+}
+
+/// Критерий принятия нового локального оптимума
+#[derive(Clone, Copy, Debug)]
+pub enum Acceptance {
+    /// Только если лучше текущего
+    Better,
+    /// Всегда (случайное блуждание по локальным оптимумам)
+    RandomWalk,
+    /// Как в отжиге: T0 = `temp_factor` * (средняя длина ребра стартового тура),
+    /// T <- alpha * T после каждой итерации
+    Annealing { temp_factor: f64, alpha: f64 },
+}
+
+#[derive(Clone, Debug)]
+pub struct IlsConfig {
+    pub time_limit: Duration,
+    /// Ограничение на число итераций поверх бюджета времени
+    pub max_iterations: usize,
+    pub kick: Kick,
+    pub acceptance: Acceptance,
+    /// Через сколько итераций без улучшения перезапуститься от лучшего тура
+    /// с усиленным возмущением (разворотами отрезков), 0 -- без рестартов
+    pub restart_after: usize,
+    /// Зерно генератора возмущений, `None` -- случайное
+    pub seed: Option<u64>,
+}
+
+impl Default for IlsConfig {
+    fn default() -> Self {
+        IlsConfig {
+            time_limit: Duration::from_secs(10),
+            max_iterations: usize::MAX,
+            kick: Kick::DoubleBridge,
+            acceptance: Acceptance::Better,
+            restart_after: 500,
+            seed: None,
+        }
+    }
+}
+
+/// Iterated Local Search: kick + 2-opt/Or-opt, пока не кончится бюджет времени
+pub struct IlsTspSolver {
+    data: TspData,
+    config: IlsConfig,
+    candidates: Vec<Vec<usize>>,
+
+    pub best_tour: Vec<usize>,
+    pub best_score: f64,
+    pub history: Vec<f64>,
+
+    rng: ChaCha8Rng,
+    cancel: CancelToken,
+    observer: Box<dyn SolverObserver>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AbcConfig {
+    /// Число источников нектара (= число рабочих пчел = число наблюдателей)
+    pub food_sources: usize,
+    pub cycles: usize,
+    /// Сколько неудачных попыток улучшить источник, прежде чем его бросит разведчик
+    pub limit: usize,
+    /// Зерно генератора колонии, `None` -- случайное
+    pub seed: Option<u64>,
+}
+
+impl Default for AbcConfig {
+    fn default() -> Self {
+        AbcConfig {
+            food_sources: 20,
+            cycles: 5000,
+            limit: 100,
+            seed: None,
+        }
+    }
+}
+
+/// Дискретная пчелиная колония (ABC): рабочие, наблюдатели и разведчики
+/// над турами с 2-opt окрестностью
+pub struct AbcTspSolver {
+    data: TspData,
+    config: AbcConfig,
+    candidates: Vec<Vec<usize>>,
+
+    // (длина, тур, число неудачных попыток)
+    sources: Vec<(f64, Vec<usize>, usize)>,
+
+    pub best_tour: Vec<usize>,
+    pub best_score: f64,
+    pub history: Vec<f64>,
+
+    rng: ChaCha8Rng,
+    cancel: CancelToken,
+    observer: Box<dyn SolverObserver>,
+}
+
+#[derive(Clone, Debug)]
+pub struct FireflyConfig {
+    pub fireflies: usize,
+    pub generations: usize,
+    /// Привлекательность на нулевом расстоянии
+    pub beta0: f64,
+    /// Коэффициент поглощения света (расстояние нормировано на n)
+    pub gamma: f64,
+    /// Число случайных 2-opt ходов при каждом перемещении
+    pub alpha: usize,
+    /// Доводить светлячков 2-opt после перемещения
+    pub local_search: bool,
+    /// Ограничение времени работы: перемещение строит разность туров за O(n^2)
+    /// для каждой пары светлячков, на больших экземплярах поколение занимает минуты
+    pub time_limit: Option<Duration>,
+    /// Зерно генератора светлячков, `None` -- случайное
+    pub seed: Option<u64>,
+}
+
+impl Default for FireflyConfig {
+    fn default() -> Self {
+        FireflyConfig {
+            fireflies: 30,
+            generations: 128,
+            beta0: 1.0,
+            gamma: 2.0,
+            alpha: 2,
+            local_search: true,
+            time_limit: None,
+            seed: None,
+        }
+    }
+}
+
+/// Дискретный алгоритм светлячков: тусклый светлячок перенимает
+/// ребра более яркого с силой, убывающей с расстоянием
+pub struct FireflyTspSolver {
+    data: TspData,
+    config: FireflyConfig,
+
+    // (длина, тур)
+    fireflies: Vec<(f64, Vec<usize>)>,
+
+    pub best_tour: Vec<usize>,
+    pub best_score: f64,
+    pub history: Vec<f64>,
+
+    rng: ChaCha8Rng,
+    cancel: CancelToken,
+    observer: Box<dyn SolverObserver>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ConstructionConfig {
+    pub method: Construction,
+    /// Доводить построенный тур 2-opt + Or-opt
+    pub local_search: bool,
+}
+
+impl Default for ConstructionConfig {
+    fn default() -> Self {
+        ConstructionConfig {
+            method: Construction::SpaceFillingCurve,
+            local_search: true,
+        }
+    }
+}
+
+/// Одна эвристика построения (+ локальный поиск) как самостоятельный солвер
+/// для огромных экземпляров, где на метаэвристику нет времени
+pub struct ConstructionTspSolver {
+    data: TspData,
+    config: ConstructionConfig,
+    candidates: Vec<Vec<usize>>,
+
+    pub best_tour: Vec<usize>,
+    pub best_score: f64,
+    pub history: Vec<f64>,
+
+    cancel: CancelToken,
+    observer: Box<dyn SolverObserver>,
+}
+
+impl AcsTspSolver {
+    pub fn new(data: TspData, n_ants: usize, n_iterations: usize, q0: f64, beta: f64) -> Self {
+        Self::with_config(
+            data,
+            AcsConfig {
+                n_ants,
+                n_iterations,
+                q0,
+                beta,
+                ..AcsConfig::default()
+            },
+        )
+    }
+
+    pub fn with_config(data: TspData, config: AcsConfig) -> Self {
+        let n = data.n;
+
+        // Кандидаты: 25 для маленьких, 40 для больших графов
+        // Для универсальности берем 30
+        debug!("precomputing candidate lists (top 30)");
+        let candidates = build_candidates(&data, 30);
+
+        // --- УЛУЧШЕННЫЙ СТАРТ ---
+        // Сразу оптимизируем жадный путь, чтобы задать высокую планку tau0
+        let mut greedy_tour = build_tour(&data, &candidates, config.construction);
+        // Применяем 2-opt к жадному старту
+        static_two_opt(&data, &mut greedy_tour);
+        let mut greedy_len = data.calculate_tour_length(&greedy_tour);
+
+        // Генератор создается до мультистарта: выбор стартовых вершин тоже зависит от зерна
+        let mut rng = seeded_rng(config.seed);
+        if config.multi_start > 0 {
+            let tour = multi_start_tour(&data, &candidates, config.multi_start, &mut rng);
+            let len = data.calculate_tour_length(&tour);
+            info!(
+                starts = config.multi_start,
+                length = len,
+                "multi-start tour {:.2}",
+                len
+            );
+            if len < greedy_len {
+                greedy_tour = tour;
+                greedy_len = len;
+            }
+        }
+
+        // Формула ACS
+        let tau0 = 1.0 / (n as f64 * greedy_len);
+
+        info!(
+            baseline = greedy_len,
+            tau0, "optimized baseline {:.2}", greedy_len
+        );
+
+        let pheromone = vec![tau0 as PheromoneType; n * n];
+
+        AcsTspSolver {
+            data,
+            config,
+            pheromone,
+            candidates,
+            tau0,
+            rng,
+            iteration: 0,
+            best_tour: greedy_tour,
+            best_score: greedy_len,
+            history: Vec::new(),
+            cancel: CancelToken::default(),
+            observer: Box::new(LogObserver::default()),
+        }
+    }
+
+    /// Продолжение с контрольной точки: феромон, лучший тур, история, состояние
+    /// генератора и номер итерации берутся из файла, параметры -- из `config`.
+    /// При тех же параметрах дальнейший ход совпадает с непрерванным запуском.
+    pub fn resume(data: TspData, config: AcsConfig, path: &Path) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let mut r = BufReader::new(File::open(path)?);
+
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != CHECKPOINT_MAGIC {
+            return Err(invalid(format!(
+                "{} is not an ACS checkpoint",
+                path.display()
+            )));
+        }
+        let n = read_u64(&mut r)? as usize;
+        if n != data.n {
+            return Err(invalid(format!(
+                "Checkpoint is for {} cities, instance has {}",
+                n, data.n
+            )));
+        }
+        let iteration = read_u64(&mut r)? as usize;
+        let tau0 = f64::from_bits(read_u64(&mut r)?);
+        let best_score = f64::from_bits(read_u64(&mut r)?);
+
+        let mut seed = [0u8; 32];
+        r.read_exact(&mut seed)?;
+        let stream = read_u64(&mut r)?;
+        let mut word_pos = [0u8; 16];
+        r.read_exact(&mut word_pos)?;
+        let mut rng = ChaCha8Rng::from_seed(seed);
+        rng.set_stream(stream);
+        rng.set_word_pos(u128::from_le_bytes(word_pos));
+
+        let best_tour = (0..n)
+            .map(|_| read_u64(&mut r).map(|c| c as usize))
+            .collect::<io::Result<Vec<_>>>()?;
+        validate_permutation(&best_tour, n)
+            .map_err(|e| invalid(format!("Checkpoint best tour: {}", e)))?;
+        // История пополняется каждой итерацией, поэтому ее длина -- номер итерации
+        let history_len = read_u64(&mut r)? as usize;
+        if history_len != iteration {
+            return Err(invalid(format!(
+                "Checkpoint history has {} entries, expected {}",
+                history_len, iteration
+            )));
+        }
+        let history = (0..history_len)
+            .map(|_| read_u64(&mut r).map(f64::from_bits))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut pheromone = vec![0.0 as PheromoneType; n * n];
+        let mut buf = [0u8; 4];
+        for tau in pheromone.iter_mut() {
+            r.read_exact(&mut buf)?;
+            *tau = PheromoneType::from_le_bytes(buf);
+        }
+
+        debug!("precomputing candidate lists (top 30)");
+        let candidates = build_candidates(&data, 30);
+        info!(
+            checkpoint = %path.display(),
+            iteration,
+            best = best_score,
+            "resumed from checkpoint"
+        );
+
+        Ok(AcsTspSolver {
+            data,
+            config,
+            pheromone,
+            candidates,
+            tau0,
+            rng,
+            iteration,
+            best_tour,
+            best_score,
+            history,
+            cancel: CancelToken::default(),
+            observer: Box::new(LogObserver::default()),
+        })
+    }
+
+    /// Сохраняет состояние во временный файл и атомарно подменяет им `path`
+    pub fn save_checkpoint(&self, path: &Path) -> io::Result<()> {
+        let tmp = path.with_extension("tmp");
+        {
+            let mut w = BufWriter::new(File::create(&tmp)?);
+            w.write_all(CHECKPOINT_MAGIC)?;
+            w.write_all(&(self.data.n as u64).to_le_bytes())?;
+            w.write_all(&(self.iteration as u64).to_le_bytes())?;
+            w.write_all(&self.tau0.to_le_bytes())?;
+            w.write_all(&self.best_score.to_le_bytes())?;
+            w.write_all(&self.rng.get_seed())?;
+            w.write_all(&self.rng.get_stream().to_le_bytes())?;
+            w.write_all(&self.rng.get_word_pos().to_le_bytes())?;
+            for &city in &self.best_tour {
+                w.write_all(&(city as u64).to_le_bytes())?;
+            }
+            w.write_all(&(self.history.len() as u64).to_le_bytes())?;
+            for h in &self.history {
+                w.write_all(&h.to_le_bytes())?;
+            }
+            for tau in &self.pheromone {
+                w.write_all(&tau.to_le_bytes())?;
+            }
+            w.flush()?;
+        }
+        fs::rename(tmp, path)
+    }
+
+    fn select_next_city(
+        &self,
+        curr: usize,
+        unvisited_mask: &[bool],
+        rng: &mut ChaCha8Rng,
+    ) -> usize {
+        // Fast candidate selection
+        let mut candidates_vec: Vec<usize> = Vec::with_capacity(30);
+        for &c in &self.candidates[curr] {
+            if unvisited_mask[c] {
+                candidates_vec.push(c);
+            }
+        }
+
+        if !candidates_vec.is_empty() {
+            if rng.random_range(0.0..1.0) <= self.config.q0 {
+                let mut best_node = candidates_vec[0];
+                let mut best_val = -1.0;
+                for &node in &candidates_vec {
+                    // Precompute distance? No, fast enough.
+                    let val = self.attractiveness(curr, node);
+                    if val > best_val {
+                        best_val = val;
+                        best_node = node;
+                    }
+                }
+                return best_node;
+            } else {
+                let mut values = Vec::with_capacity(candidates_vec.len());
+                let mut sum = 0.0;
+                for &node in &candidates_vec {
+                    let val = self.attractiveness(curr, node);
+                    values.push(val);
+                    sum += val;
+                }
+                if sum == 0.0 {
+                    return candidates_vec[0];
+                }
+                let r = rng.random_range(0.0..1.0) * sum;
+                let mut acc = 0.0;
+                for (i, &v) in values.iter().enumerate() {
+                    acc += v;
+                    if acc >= r {
+                        return candidates_vec[i];
+                    }
+                }
+                return *candidates_vec.last().unwrap();
+            }
+        }
+
+        // Fallback
+        unvisited_mask.iter().position(|&u| u).unwrap_or(0)
+    }
+
+    // tau^alpha * eta^beta
+    #[inline(always)]
+    fn attractiveness(&self, curr: usize, node: usize) -> PheromoneType {
+        let tau = self.pheromone[curr * self.data.n + node];
+        let tau = if self.config.alpha == 1.0 {
+            tau
+        } else {
+            tau.powf(self.config.alpha as PheromoneType)
+        };
+        tau * (1.0 / self.data.dist(curr, node)).powf(self.config.beta) as PheromoneType
+    }
+
+    fn local_update(&mut self, u: usize, v: usize) {
+        let idx = u * self.data.n + v;
+        let idx2 = v * self.data.n + u;
+        self.pheromone[idx] = (1.0 - self.config.phi) as PheromoneType * self.pheromone[idx]
+            + (self.config.phi * self.tau0) as PheromoneType;
+        self.pheromone[idx2] = self.pheromone[idx];
+    }
+
+    fn global_update(&mut self, ants: &[(f64, Vec<usize>)], iter_best: usize, it: usize) {
+        // Опорный тур выбирается политикой отложения
+        let (ref_score, ref_tour) = if self.config.deposit.use_global_best(it) {
+            (self.best_score, &self.best_tour)
+        } else {
+            (ants[iter_best].0, &ants[iter_best].1)
+        };
+        let rho = self.config.rho;
+        let pheromone = &mut self.pheromone;
+
+        match self.config.update_rule {
+            UpdateRule::Acs => {
+                let deposit = 1.0 / ref_score;
+                let n = ref_tour.len();
+                for i in 0..n {
+                    let u = ref_tour[i];
+                    let v = ref_tour[(i + 1) % n];
+                    let idx = u * n + v;
+                    let idx2 = v * n + u;
+                    pheromone[idx] = (1.0 - rho) as PheromoneType * pheromone[idx]
+                        + (rho * deposit) as PheromoneType;
+                    pheromone[idx2] = pheromone[idx];
+                }
+            }
+            UpdateRule::Elitist { weight } => {
+                Self::evaporate(pheromone, rho);
+                for (score, tour) in ants {
+                    Self::deposit(pheromone, tour, 1.0 / score);
+                }
+                Self::deposit(pheromone, ref_tour, weight / ref_score);
+            }
+            UpdateRule::RankBased { w } => {
+                Self::evaporate(pheromone, rho);
+                let mut order: Vec<usize> = (0..ants.len()).collect();
+                order.sort_by(|&a, &b| ants[a].0.partial_cmp(&ants[b].0).unwrap());
+                for (r, &k) in order.iter().take(w.saturating_sub(1)).enumerate() {
+                    Self::deposit(pheromone, &ants[k].1, (w - 1 - r) as f64 / ants[k].0);
+                }
+                Self::deposit(pheromone, ref_tour, w as f64 / ref_score);
+            }
+        }
+    }
+
+    /// Теплый старт: `tour` становится лучшим, tau0 пересчитывается по его длине,
+    /// а на его ребра кладется феромон уровня 1/L (равновесие глобального обновления ACS)
+    pub fn warm_start(&mut self, tour: &[usize]) -> Result<(), String> {
+        validate_permutation(tour, self.data.n).map_err(|e| e.to_string())?;
+        let n = self.data.n;
+        let len = self.data.calculate_tour_length(tour);
+
+        self.tau0 = 1.0 / (n as f64 * len);
+        self.pheromone.fill(self.tau0 as PheromoneType);
+        Self::deposit(&mut self.pheromone, tour, 1.0 / len - self.tau0);
+
+        self.best_tour = tour.to_vec();
+        self.best_score = len;
+        info!(length = len, tau0 = self.tau0, "warm start {:.2}", len);
+        Ok(())
+    }
+
+    fn evaporate(pheromone: &mut [PheromoneType], rho: f64) {
+        let evaporation = (1.0 - rho) as PheromoneType;
+        for tau in pheromone.iter_mut() {
+            *tau *= evaporation;
+        }
+    }
+
+    fn deposit(pheromone: &mut [PheromoneType], tour: &[usize], amount: f64) {
+        let n = tour.len();
+        for i in 0..n {
+            let u = tour[i];
+            let v = tour[(i + 1) % n];
+            pheromone[u * n + v] += amount as PheromoneType;
+            pheromone[v * n + u] = pheromone[u * n + v];
+        }
+    }
+
+    pub fn run(&mut self) {
+        let params = format!(
+            "alpha={}, beta={}, ants={}, update={:?}, deposit={:?}",
+            self.config.alpha,
+            self.config.beta,
+            self.config.n_ants,
+            self.config.update_rule,
+            self.config.deposit
+        );
+        self.observer.on_start("ACS", &params);
+        let mut rng = self.rng.clone();
+        let n = self.data.n;
+        // Локальное обновление есть только в ACS
+        let local = matches!(self.config.update_rule, UpdateRule::Acs);
+
+        // Без муравьев итерации пусты: остается стартовый тур
+        let n_iterations = if self.config.n_ants == 0 {
+            warn!("n_ants = 0, iterations skipped");
+            self.iteration
+        } else {
+            self.config.n_iterations
+        };
+        for it in self.iteration..n_iterations {
+            if self.cancel.is_cancelled() {
+                self.observer.on_event(it, "interrupted");
+                // Сохраняем состояние, чтобы продолжить с этой итерации
+                if let Some(ckpt) = &self.config.checkpoint
+                    && let Err(e) = self.save_checkpoint(&ckpt.path)
+                {
+                    warn!(error = %e, "checkpoint failed");
+                }
+                break;
+            }
+            // Храним туры всех муравьев итерации (нужны Elitist/Rank-based)
+            let mut ants: Vec<(f64, Vec<usize>)> = Vec::with_capacity(self.config.n_ants);
+
+            for _k in 0..self.config.n_ants {
+                let mut tour = Vec::with_capacity(n);
+                let start_node = rng.random_range(0..n);
+                tour.push(start_node);
+
+                let mut mask = vec![true; n];
+                mask[start_node] = false;
+                let mut count = 1;
+                let mut curr = start_node;
+
+                while count < n {
+                    let next = self.select_next_city(curr, &mask, &mut rng);
+                    mask[next] = false;
+                    if local {
+                        self.local_update(curr, next);
+                    }
+                    tour.push(next);
+                    curr = next;
+                    count += 1;
+                }
+                if local {
+                    self.local_update(tour[n - 1], tour[0]);
+                }
+
+                // 1. Все делают быстрый 2-opt
+                two_opt_fast(&self.data, &mut tour);
+                let score = self.data.calculate_tour_length(&tour);
+                ants.push((score, tour));
+            }
+
+            // Запоминаем лучшего в итерации
+            let best_idx = (0..ants.len())
+                .min_by(|&a, &b| ants[a].0.partial_cmp(&ants[b].0).unwrap())
+                .unwrap();
+            let (mut iter_best_score, mut iter_best_tour) = std::mem::take(&mut ants[best_idx]);
+
+            // 2. СТРАТЕГИЯ "ЧЕМПИОН ИТЕРАЦИИ"
+            // Только победитель гонки получает Or-opt.
+            // Это гарантирует, что мы всегда пытаемся улучшить лучший результат,
+            // но не тратим время на остальных.
+            if or_opt(&self.data, &self.candidates, &mut iter_best_tour) {
+                // Если улучшили, пересчитываем скор
+                // iter_best_score = self.data.calculate_tour_length(&iter_best_tour);
+                // И полируем еще раз 2-opt'ом
+                two_opt_fast(&self.data, &mut iter_best_tour);
+                iter_best_score = self.data.calculate_tour_length(&iter_best_tour);
+            }
+
+            // Обновляем глобальный рекорд
+            if iter_best_score < self.best_score {
+                self.best_score = iter_best_score;
+                self.best_tour = iter_best_tour.clone();
+                self.observer
+                    .on_improvement(it, &self.best_tour, self.best_score);
+            }
+            ants[best_idx] = (iter_best_score, iter_best_tour);
+
+            // Глобальное обновление феромонов по выбранному правилу
+            self.global_update(&ants, best_idx, it);
+            self.history.push(self.best_score);
+            let (entropy, branching) = if self.observer.wants_pheromone_stats() {
+                let (entropy, branching) = pheromone_stats(&self.pheromone, &self.candidates);
+                (Some(entropy), Some(branching))
+            } else {
+                (None, None)
+            };
+            self.observer.on_iteration(&IterationStats {
+                iteration: it,
+                best: self.best_score,
+                iteration_best: Some(ants[best_idx].0),
+                mean: Some(ants.iter().map(|a| a.0).sum::<f64>() / ants.len() as f64),
+                entropy,
+                branching,
+                diversity: None,
+            });
+
+            self.iteration = it + 1;
+            self.rng = rng.clone();
+            if let Some(ckpt) = &self.config.checkpoint
+                && ckpt.every > 0
+                && self.iteration.is_multiple_of(ckpt.every)
+                && let Err(e) = self.save_checkpoint(&ckpt.path)
+            {
+                warn!(error = %e, "checkpoint failed");
+            }
+        }
+        self.observer.on_finish(self.history.len(), self.best_score);
+    }
+}
+
+const CHECKPOINT_MAGIC: &[u8; 8] = b"ACSCKPT1";
+
+fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+// Энтропия и lambda-branching factor феромона по спискам кандидатов, усредненные по вершинам:
+// энтропия нормирована на ln k (1 -- следы равномерны), branching -- число ребер с
+// tau >= tau_min + 0.05 * (tau_max - tau_min) в пределах списка вершины
+fn pheromone_stats(pheromone: &[PheromoneType], candidates: &[Vec<usize>]) -> (f64, f64) {
+    let n = candidates.len();
+    let (mut entropy, mut branching) = (0.0, 0.0);
+    for (i, list) in candidates.iter().enumerate() {
+        if list.len() < 2 {
+            continue;
+        }
+        let taus: Vec<f64> = list.iter().map(|&j| pheromone[i * n + j] as f64).collect();
+        let total: f64 = taus.iter().sum();
+        let h: f64 = taus
+            .iter()
+            .filter(|&&t| t > 0.0)
+            .map(|&t| {
+                let p = t / total;
+                -p * p.ln()
+            })
+            .sum();
+        entropy += h / (taus.len() as f64).ln();
+
+        let lo = taus.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = taus.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let threshold = lo + 0.05 * (hi - lo);
+        branching += taus.iter().filter(|&&t| t >= threshold).count() as f64;
+    }
+    (entropy / n as f64, branching / n as f64)
+}
+
+// Разнообразие популяции туров: средняя доля ребер, которых нет в `reference`
+fn population_diversity<'a>(tours: impl Iterator<Item = &'a [usize]>, reference: &[usize]) -> f64 {
+    let n = reference.len();
+    let adj = GaTspSolver::adjacency(reference);
+    let (mut total, mut count) = (0.0, 0);
+    for tour in tours {
+        let differ = (0..n)
+            .filter(|&k| !adj[tour[k]].contains(&tour[(k + 1) % n]))
+            .count();
+        total += differ as f64 / n as f64;
+        count += 1;
+    }
+    if count == 0 {
+        0.0
+    } else {
+        total / count as f64
+    }
+}
+
+// Тур 0, 1, ..., n-1 с его длиной: стартовый лучший тур, когда популяция пуста
+fn identity_tour(data: &TspData) -> (f64, Vec<usize>) {
+    let tour: Vec<usize> = (0..data.n).collect();
+    (data.calculate_tour_length(&tour), tour)
+}
+
+// Списки кандидатов: k ближайших соседей для каждой вершины
+pub(crate) fn build_candidates(data: &TspData, k: usize) -> Vec<Vec<usize>> {
+    let n = data.n;
+    let mut candidates = vec![Vec::new(); n];
+
+    for (i, list) in candidates.iter_mut().enumerate() {
+        let mut dists: Vec<(usize, f64)> = (0..n)
+            .filter(|&j| i != j)
+            .map(|j| (j, data.dist(i, j)))
+            .collect();
+        dists.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        *list = dists.iter().take(k).map(|x| x.0).collect();
+    }
+    candidates
+}
+
+// Статическая оптимизация при инициализации
+pub(crate) fn static_two_opt(data: &TspData, tour: &mut [usize]) {
+    let n = tour.len();
+    let mut improved = true;
+    let mut passes = 0;
+    while improved && passes < 5 {
+        improved = false;
+        passes += 1;
+        for i in 1..(n - 1) {
+            let limit = std::cmp::min(n, i + 200);
+            for j in (i + 1)..limit {
+                if j - i == 1 {
+                    continue;
+                }
+                let u = tour[i - 1];
+                let v = tour[i];
+                let w = tour[j];
+                let z = tour[(j + 1) % n];
+                if data.dist(u, w) + data.dist(v, z) < data.dist(u, v) + data.dist(w, z) - 1e-8 {
+                    tour[i..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+}
+
+// NN из `starts` различных вершин (0 и случайные) с доводкой 2-opt/Or-opt до сходимости,
+// старты делятся между потоками; возвращает лучший тур
+pub(crate) fn multi_start_tour(
+    data: &TspData,
+    candidates: &[Vec<usize>],
+    starts: usize,
+    rng: &mut impl Rng,
+) -> Vec<usize> {
+    let mut cities: Vec<usize> = (1..data.n).collect();
+    cities.shuffle(rng);
+    cities.insert(0, 0);
+    cities.truncate(starts.clamp(1, data.n));
+
+    let threads = std::thread::available_parallelism()
+        .map_or(1, |p| p.get())
+        .min(cities.len());
+    let chunk = cities.len().div_ceil(threads);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = cities
+            .chunks(chunk)
+            .map(|part| {
+                scope.spawn(move || {
+                    let mut best: Option<(f64, Vec<usize>)> = None;
+                    for &start in part {
+                        let mut tour = nearest_neighbour_tour(data, candidates, start);
+                        // Чередуем 2-opt и Or-opt, пока Or-opt находит улучшения
+                        for _ in 0..10 {
+                            two_opt_fast(data, &mut tour);
+                            if !or_opt(data, candidates, &mut tour) {
+                                break;
+                            }
+                        }
+                        let len = data.calculate_tour_length(&tour);
+                        if best.as_ref().is_none_or(|b| len < b.0) {
+                            best = Some((len, tour));
+                        }
+                    }
+                    best.unwrap()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+            .unwrap()
+            .1
+    })
+}
+
+// Быстрый 2-opt (Windowed)
+pub(crate) fn two_opt_fast(data: &TspData, tour: &mut [usize]) {
+    let n = tour.len();
+    let mut improved = true;
+    let mut passes = 0;
+
+    // Лимит 8 проходов - баланс скорости и качества
+    while improved && passes < 8 {
+        improved = false;
+        passes += 1;
+
+        for i in 1..(n - 1) {
+            // Окно 200 для скорости на больших графах
+            let limit = std::cmp::min(n, i + 200);
+            for j in (i + 1)..limit {
+                if j - i == 1 {
+                    continue;
+                }
+                let u = tour[i - 1];
+                let v = tour[i];
+                let w = tour[j];
+                let z = tour[(j + 1) % n];
+
+                if data.dist(u, w) + data.dist(v, z) < data.dist(u, v) + data.dist(w, z) - 1e-8 {
+                    tour[i..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+}
+
+// Or-opt (Heavy)
+// Or-opt ход: перенести блок tour[i..i + len] сразу за город на позиции `target`
+// (вне блока и не перед ним). Сдвигается только отрезок между блоком и целью,
+// `pos` обновляется на нем же
+fn move_block(tour: &mut [usize], pos: &mut [usize], i: usize, len: usize, target: usize) {
+    let range = if target > i {
+        tour[i..=target].rotate_left(len);
+        i..=target
+    } else {
+        tour[target + 1..i + len].rotate_right(len);
+        target + 1..=i + len - 1
+    };
+    for k in range {
+        pos[tour[k]] = k;
+    }
+}
+
+pub(crate) fn or_opt(data: &TspData, candidates: &[Vec<usize>], tour: &mut [usize]) -> bool {
+    let n = tour.len();
+    let mut improved = false;
+    let mut pos = vec![0; n];
+    for (i, &node) in tour.iter().enumerate() {
+        pos[node] = i;
+    }
+
+    for &block_size in &[3, 2, 1] {
+        let mut continue_search = true;
+        let mut moves_limit = 0;
+
+        // Разрешаем не более 10 перестановок за вызов, чтобы не виснуть
+        while continue_search && moves_limit < 10 {
+            continue_search = false;
+
+            for i in 0..n {
+                if i + block_size >= n - 1 {
+                    continue;
+                }
+
+                let start_node = tour[i];
+                let end_node = tour[i + block_size - 1];
+                let prev_node = tour[if i == 0 { n - 1 } else { i - 1 }];
+                let next_node = tour[i + block_size];
+
+                let cost_rem = data.dist(prev_node, start_node) + data.dist(end_node, next_node);
+                let cost_add = data.dist(prev_node, next_node);
+                let reduction = cost_rem - cost_add;
+
+                // Ищем куда вставить, проверяя кандидатов start_node
+                for &target_node in &candidates[start_node] {
+                    let target_idx = pos[target_node];
+                    // Цель -- не предыдущий город, не блок и не следующий за ним
+                    if target_idx == (i + n - 1) % n
+                        || (target_idx >= i && target_idx <= i + block_size)
+                    {
+                        continue;
+                    }
+
+                    let target_next_idx = (target_idx + 1) % n;
+                    let target_next_node = tour[target_next_idx];
+
+                    let cost_ins =
+                        data.dist(target_node, start_node) + data.dist(end_node, target_next_node);
+                    let cost_break = data.dist(target_node, target_next_node);
+
+                    if reduction > (cost_ins - cost_break) + 1e-6 {
+                        move_block(tour, &mut pos, i, block_size, target_idx);
+                        improved = true;
+                        continue_search = true;
+                        moves_limit += 1;
+                        break;
+                    }
+                }
+                if continue_search {
+                    break;
+                }
+            }
+        }
+    }
+    improved
+}
+
+impl PsoTspSolver {
+    pub fn new(
+        data: TspData,
+        num_particles: usize,
+        iterations: usize,
+        w: f64,
         c1: f64,
         c2: f64,
     ) -> Self {
+        Self::with_config(
+            data,
+            PsoConfig {
+                num_particles,
+                iterations,
+                inertia: InertiaSchedule::Constant(w),
+                c1,
+                c2,
+                ..PsoConfig::default()
+            },
+        )
+    }
+
+    pub fn with_config(data: TspData, config: PsoConfig) -> Self {
+        let n_cities = data.n;
+        let num_particles = config.num_particles;
+        let mut rng = seeded_rng(config.seed);
+
+        // Диапазон для инициализации координат и скоростей
+        let pos_dist = Uniform::new(0.0, 1.0).unwrap();
+        let vel_dist_scale = Uniform::new(0.0, 1.0).unwrap(); // Для скоростей
+
+        // Инициализация частиц
+        let mut positions = vec![vec![0.0; n_cities]; num_particles];
+        let mut velocities = vec![vec![0.0; n_cities]; num_particles];
+        let mut pbest_pos = vec![vec![0.0; n_cities]; num_particles];
+        let mut pbest_scores = vec![f64::INFINITY; num_particles];
+
+        // Лучшее глобальное
+        let mut gbest_pos = vec![0.0; n_cities];
+        let mut gbest_score = f64::INFINITY;
+        let mut gbest_tour = Vec::new();
+
+        // Создаем начальный рой
+        for i in 0..num_particles {
+            for j in 0..n_cities {
+                positions[i][j] = pos_dist.sample(&mut rng);
+                velocities[i][j] = vel_dist_scale.sample(&mut rng);
+            }
+            if let Some(v_max) = config.v_max {
+                for v in velocities[i].iter_mut() {
+                    *v = f64::clamp(*v, -v_max, v_max);
+                }
+            }
+
+            // Находим начальный лучший путь для этой частицы
+            let current_tour = Self::get_tour(&positions[i]);
+            let current_score = data.calculate_tour_length(&current_tour);
+
+            pbest_pos[i] = positions[i].clone();
+            pbest_scores[i] = current_score;
+
+            if current_score < gbest_score {
+                gbest_score = current_score;
+                gbest_pos = positions[i].clone();
+                gbest_tour = current_tour;
+            }
+        }
+
+        let candidates = if config.local_search {
+            build_candidates(&data, 30)
+        } else {
+            Vec::new()
+        };
+
+        PsoTspSolver {
+            data,
+            config,
+            candidates,
+            positions,
+            velocities,
+            pbest_pos,
+            pbest_scores,
+            gbest_pos,
+            gbest_tour,
+            gbest_score,
+            history: Vec::new(),
+            rng,
+            cancel: CancelToken::default(),
+            observer: Box::new(LogObserver::default()),
+        }
+    }
+
+    // Преобразование вектора приоритетов в перестановку
+    fn get_tour(position_vector: &[f64]) -> Vec<usize> {
+        let mut indexed_values: Vec<(usize, f64)> =
+            position_vector.iter().cloned().enumerate().collect();
+        // Сортируем по значению (приоритету)
+        indexed_values.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        // Возвращаем только индексы городов в отсортированном порядке
+        indexed_values.iter().map(|&(index, _)| index).collect()
+    }
+
+    // Обратное преобразование: переставляем те же значения ключей так,
+    // чтобы их argsort давал `tour`
+    fn set_tour(position_vector: &mut [f64], tour: &[usize]) {
+        let mut values = position_vector.to_vec();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for (&city, value) in tour.iter().zip(values) {
+            position_vector[city] = value;
+        }
+    }
+
+    /// Теплый старт: `tour` становится gbest и позицией/pbest нулевой частицы
+    pub fn warm_start(&mut self, tour: &[usize]) -> Result<(), String> {
+        validate_permutation(tour, self.data.n).map_err(|e| e.to_string())?;
+        if self.positions.is_empty() {
+            return Err("num_particles = 0, no particle to warm start".to_string());
+        }
+        let n = tour.len();
+        let mut keys = vec![0.0; n];
+        for (k, &city) in tour.iter().enumerate() {
+            keys[city] = k as f64 / n as f64;
+        }
+        let score = self.data.calculate_tour_length(tour);
+
+        self.positions[0] = keys.clone();
+        self.pbest_pos[0] = keys.clone();
+        self.pbest_scores[0] = score;
+        if score < self.gbest_score {
+            self.gbest_pos = keys;
+            self.gbest_tour = tour.to_vec();
+            self.gbest_score = score;
+        }
+        info!(length = score, "warm start {:.2}", score);
+        Ok(())
+    }
+
+    // Разнообразие роя: среднее евклидово расстояние ключей частиц до центроида
+    fn swarm_diversity(positions: &[Vec<f64>]) -> f64 {
+        let Some(first) = positions.first() else {
+            return 0.0;
+        };
+        let m = positions.len() as f64;
+        let n = first.len();
+        let mut centroid = vec![0.0; n];
+        for p in positions {
+            for (c, x) in centroid.iter_mut().zip(p) {
+                *c += x / m;
+            }
+        }
+        positions
+            .iter()
+            .map(|p| {
+                p.iter()
+                    .zip(&centroid)
+                    .map(|(x, c)| (x - c) * (x - c))
+                    .sum::<f64>()
+                    .sqrt()
+            })
+            .sum::<f64>()
+            / m
+    }
+
+    // Индекс лучшей частицы (по pbest) в окрестности частицы i
+    fn neighbourhood_best(&self, i: usize) -> usize {
+        let m = self.config.num_particles;
+        let neighbours: Vec<usize> = match self.config.topology {
+            Topology::Global => return i,
+            Topology::Ring => vec![(i + m - 1) % m, i, (i + 1) % m],
+            Topology::VonNeumann => {
+                // Решетка cols x rows, близкая к квадратной, замкнутая в тор
+                let cols = (m as f64).sqrt().ceil() as usize;
+                let rows = m.div_ceil(cols);
+                let (r, c) = (i / cols, i % cols);
+                [
+                    i,
+                    ((r + rows - 1) % rows) * cols + c,
+                    ((r + 1) % rows) * cols + c,
+                    r * cols + (c + cols - 1) % cols,
+                    r * cols + (c + 1) % cols,
+                ]
+                .into_iter()
+                .filter(|&k| k < m)
+                .collect()
+            }
+        };
+        neighbours
+            .into_iter()
+            .min_by(|&a, &b| {
+                self.pbest_scores[a]
+                    .partial_cmp(&self.pbest_scores[b])
+                    .unwrap()
+            })
+            .unwrap()
+    }
+
+    pub fn run(&mut self) {
+        let params = if self.config.local_search {
+            format!(
+                "hybrid, {:?} topology, v_max={:?}",
+                self.config.topology, self.config.v_max
+            )
+        } else {
+            "classic".to_string()
+        };
+        self.observer.on_start("PSO", &params);
+        let mut rng = self.rng.clone();
+        let n_cities = self.data.n;
+
+        // Генераторы случайных чисел для r1, r2
+        let r1_dist = Uniform::new(0.0, 1.0).unwrap();
+        let r2_dist = Uniform::new(0.0, 1.0).unwrap();
+
+        // Без частиц итерации пусты: остается стартовый (или теплый) тур
+        let iterations = if self.config.num_particles == 0 {
+            warn!("num_particles = 0, iterations skipped");
+            0
+        } else {
+            self.config.iterations
+        };
+        for it in 0..iterations {
+            if self.cancel.is_cancelled() {
+                self.observer.on_event(it, "interrupted");
+                break;
+            }
+            let mut current_iter_best = f64::INFINITY;
+            let mut score_sum = 0.0;
+            let gbest_before = self.gbest_score;
+
+            // 1. Оценка каждой частицы
+            for i in 0..self.config.num_particles {
+                let mut current_tour = Self::get_tour(&self.positions[i]);
+                let mut current_score = self.data.calculate_tour_length(&current_tour);
+
+                // Обновление PBest
+                if current_score < self.pbest_scores[i] {
+                    // Гибрид: доводим новый pbest локальным поиском и записываем обратно в ключи
+                    if self.config.local_search {
+                        two_opt_fast(&self.data, &mut current_tour);
+                        if or_opt(&self.data, &self.candidates, &mut current_tour) {
+                            two_opt_fast(&self.data, &mut current_tour);
+                        }
+                        current_score = self.data.calculate_tour_length(&current_tour);
+                        Self::set_tour(&mut self.positions[i], &current_tour);
+                    }
+                    self.pbest_scores[i] = current_score;
+                    self.pbest_pos[i] = self.positions[i].clone();
+                }
+
+                // Обновление GBest
+                if current_score < self.gbest_score {
+                    self.gbest_score = current_score;
+                    self.gbest_pos = self.positions[i].clone();
+                    self.gbest_tour = current_tour;
+                }
+                current_iter_best = current_iter_best.min(current_score);
+                score_sum += current_score;
+            }
+            if self.gbest_score < gbest_before {
+                self.observer
+                    .on_improvement(it, &self.gbest_tour, self.gbest_score);
+            }
+            self.history.push(self.gbest_score);
+            let n_particles = self.config.num_particles as f64;
+            self.observer.on_iteration(&IterationStats {
+                iteration: it,
+                best: self.gbest_score,
+                iteration_best: Some(current_iter_best),
+                mean: Some(score_sum / n_particles),
+                diversity: Some(Self::swarm_diversity(&self.positions)),
+                ..IterationStats::default()
+            });
+
+            let w = self.config.inertia.at(it, self.config.iterations);
+
+            // 2. Обновление скоростей и позиций
+            for i in 0..self.config.num_particles {
+                let r1 = r1_dist.sample(&mut rng);
+                let r2 = r2_dist.sample(&mut rng);
+                let social = match self.config.topology {
+                    Topology::Global => None,
+                    _ => Some(self.neighbourhood_best(i)),
+                };
+
+                for j in 0..n_cities {
+                    let guide = match social {
+                        None => self.gbest_pos[j],
+                        Some(k) => self.pbest_pos[k][j],
+                    };
+                    // Скорость: v = w*v + c1*r1*(pbest - x) + c2*r2*(lbest - x)
+                    let mut velocity_component = w * self.velocities[i][j]
+                        + self.config.c1 * r1 * (self.pbest_pos[i][j] - self.positions[i][j])
+                        + self.config.c2 * r2 * (guide - self.positions[i][j]);
+                    if let Some(v_max) = self.config.v_max {
+                        velocity_component = velocity_component.clamp(-v_max, v_max);
+                    }
+
+                    self.velocities[i][j] = velocity_component;
+
+                    // Позиция: x = x + v
+                    self.positions[i][j] += self.velocities[i][j];
+
+                    // Ограничение позиций в [0, 1] (если нужно)
+                    // self.positions[i][j] = self.positions[i][j].clamp(0.0, 1.0);
+                    // В Random Keys claming не всегда нужен, т.к. argsort работает с любыми числами
+                }
+            }
+        }
+        // Повторный run продолжает ту же последовательность, а не начинает ее заново
+        self.rng = rng;
+
+        self.observer
+            .on_finish(self.history.len(), self.gbest_score);
+    }
+}
+
+impl MmasTspSolver {
+    pub fn new(data: TspData, config: MmasConfig) -> Self {
+        let rng = seeded_rng(config.seed);
+        let n = data.n;
+
+        debug!("precomputing candidate lists (top 30)");
+        let candidates = build_candidates(&data, 30);
+
+        let mut greedy_tour = greedy_initial_tour(&data, &candidates);
+        static_two_opt(&data, &mut greedy_tour);
+        let greedy_len = data.calculate_tour_length(&greedy_tour);
+
+        // MMAS стартует с верхней границы, чтобы усилить начальное исследование
+        let (tau_min, tau_max) = Self::trail_limits(&config, n, greedy_len);
+        info!(
+            baseline = greedy_len,
+            tau_min, tau_max, "optimized baseline {:.2}", greedy_len
+        );
+
+        MmasTspSolver {
+            data,
+            pheromone: vec![tau_max as PheromoneType; n * n],
+            candidates,
+            tau_min,
+            tau_max,
+            restart_best_tour: greedy_tour.clone(),
+            restart_best_score: greedy_len,
+            best_tour: greedy_tour,
+            best_score: greedy_len,
+            history: Vec::new(),
+            rng,
+            cancel: CancelToken::default(),
+            observer: Box::new(LogObserver::default()),
+            config,
+        }
+    }
+
+    // Границы феромона по формулам Штютцле-Хоса
+    fn trail_limits(config: &MmasConfig, n: usize, best_len: f64) -> (f64, f64) {
+        let tau_max = 1.0 / (config.rho * best_len);
+        let p_dec = config.p_best.powf(1.0 / n as f64);
+        let avg = (n as f64 / 2.0).max(2.0);
+        let tau_min = (tau_max * (1.0 - p_dec) / ((avg - 1.0) * p_dec)).min(tau_max);
+        (tau_min, tau_max)
+    }
+
+    fn select_next_city(
+        &self,
+        curr: usize,
+        unvisited_mask: &[bool],
+        rng: &mut ChaCha8Rng,
+    ) -> usize {
+        let n = self.data.n;
+        let mut candidates_vec: Vec<usize> = Vec::with_capacity(30);
+        let mut values: Vec<f64> = Vec::with_capacity(30);
+        let mut sum = 0.0;
+        for &node in &self.candidates[curr] {
+            if unvisited_mask[node] {
+                let tau = self.pheromone[curr * n + node] as f64;
+                let val = tau.powf(self.config.alpha)
+                    * (1.0 / self.data.dist(curr, node)).powf(self.config.beta);
+                candidates_vec.push(node);
+                values.push(val);
+                sum += val;
+            }
+        }
+
+        if !candidates_vec.is_empty() {
+            if sum == 0.0 {
+                return candidates_vec[0];
+            }
+            let r = rng.random_range(0.0..1.0) * sum;
+            let mut acc = 0.0;
+            for (i, &v) in values.iter().enumerate() {
+                acc += v;
+                if acc >= r {
+                    return candidates_vec[i];
+                }
+            }
+            return *candidates_vec.last().unwrap();
+        }
+
+        // Fallback: ближайшая непосещенная вершина
+        let mut best_node = 0;
+        let mut best_dist = f64::INFINITY;
+        for (j, _) in unvisited_mask.iter().enumerate().filter(|&(_, &u)| u) {
+            let d = self.data.dist(curr, j);
+            if d < best_dist {
+                best_dist = d;
+                best_node = j;
+            }
+        }
+        best_node
+    }
+
+    fn global_update(&mut self, tour: &[usize], score: f64) {
+        let n = self.data.n;
+        let evaporation = (1.0 - self.config.rho) as PheromoneType;
+        for tau in self.pheromone.iter_mut() {
+            *tau *= evaporation;
+        }
+
+        let deposit = (1.0 / score) as PheromoneType;
+        for i in 0..n {
+            let u = tour[i];
+            let v = tour[(i + 1) % n];
+            self.pheromone[u * n + v] += deposit;
+            self.pheromone[v * n + u] = self.pheromone[u * n + v];
+        }
+
+        let (lo, hi) = (self.tau_min as PheromoneType, self.tau_max as PheromoneType);
+        for tau in self.pheromone.iter_mut() {
+            *tau = tau.clamp(lo, hi);
+        }
+    }
+
+    // Pheromone Trail Smoothing: подтягиваем все следы к tau_max
+    fn smooth_trails(&mut self, delta: f64) {
+        let tau_max = self.tau_max as PheromoneType;
+        let delta = delta as PheromoneType;
+        for tau in self.pheromone.iter_mut() {
+            *tau += delta * (tau_max - *tau);
+        }
+    }
+
+    pub fn run(&mut self) {
+        let params = format!(
+            "alpha={}, beta={}, rho={}, ants={}",
+            self.config.alpha, self.config.beta, self.config.rho, self.config.n_ants
+        );
+        self.observer.on_start("MMAS", &params);
+        let mut rng = self.rng.clone();
+        let n = self.data.n;
+
+        let mut since_restart = 0;
+        let mut stagnation = 0;
+        let mut smoothed = false;
+
+        // Как и в ACS: без муравьев остается стартовый тур
+        let n_iterations = if self.config.n_ants == 0 {
+            warn!("n_ants = 0, iterations skipped");
+            0
+        } else {
+            self.config.n_iterations
+        };
+        for it in 0..n_iterations {
+            if self.cancel.is_cancelled() {
+                self.observer.on_event(it, "interrupted");
+                break;
+            }
+            let mut iter_best_score = f64::INFINITY;
+            let mut iter_best_tour = Vec::new();
+            let mut score_sum = 0.0;
+
+            for _k in 0..self.config.n_ants {
+                let mut tour = Vec::with_capacity(n);
+                let start_node = rng.random_range(0..n);
+                tour.push(start_node);
+
+                let mut mask = vec![true; n];
+                mask[start_node] = false;
+                let mut curr = start_node;
+
+                for _ in 1..n {
+                    let next = self.select_next_city(curr, &mask, &mut rng);
+                    mask[next] = false;
+                    tour.push(next);
+                    curr = next;
+                }
+
+                two_opt_fast(&self.data, &mut tour);
+                let score = self.data.calculate_tour_length(&tour);
+                score_sum += score;
+
+                if score < iter_best_score {
+                    iter_best_score = score;
+                    iter_best_tour = tour;
+                }
+            }
+
+            // Как и в ACS, Or-opt получает только чемпион итерации
+            if or_opt(&self.data, &self.candidates, &mut iter_best_tour) {
+                two_opt_fast(&self.data, &mut iter_best_tour);
+                iter_best_score = self.data.calculate_tour_length(&iter_best_tour);
+            }
+
+            if iter_best_score < self.restart_best_score - 1e-9 {
+                self.restart_best_score = iter_best_score;
+                self.restart_best_tour = iter_best_tour.clone();
+                stagnation = 0;
+                smoothed = false;
+            } else {
+                stagnation += 1;
+            }
+
+            if iter_best_score < self.best_score {
+                self.best_score = iter_best_score;
+                self.best_tour = iter_best_tour.clone();
+                (self.tau_min, self.tau_max) = Self::trail_limits(&self.config, n, self.best_score);
+                self.observer
+                    .on_improvement(it, &self.best_tour, self.best_score);
+            }
+
+            // Откладываем феромон по расписанию: лучший в итерации или лучший с рестарта
+            if self.config.deposit.use_global_best(since_restart) {
+                let tour = std::mem::take(&mut self.restart_best_tour);
+                self.global_update(&tour, self.restart_best_score);
+                self.restart_best_tour = tour;
+            } else {
+                self.global_update(&iter_best_tour, iter_best_score);
+            }
+            since_restart += 1;
+
+            // Стагнация: сначала сглаживаем следы, при повторной -- полный рестарт
+            if stagnation >= self.config.stagnation_limit {
+                if !smoothed && self.config.smoothing < 1.0 {
+                    self.observer.on_event(it, "stagnation, smoothing trails");
+                    self.smooth_trails(self.config.smoothing);
+                    smoothed = true;
+                } else {
+                    self.observer
+                        .on_event(it, "stagnation, reinitialising trails");
+                    self.pheromone.fill(self.tau_max as PheromoneType);
+                    self.restart_best_tour = self.best_tour.clone();
+                    self.restart_best_score = self.best_score;
+                    since_restart = 0;
+                    smoothed = false;
+                }
+                stagnation = 0;
+            }
+
+            self.history.push(self.best_score);
+            let (entropy, branching) = if self.observer.wants_pheromone_stats() {
+                let (entropy, branching) = pheromone_stats(&self.pheromone, &self.candidates);
+                (Some(entropy), Some(branching))
+            } else {
+                (None, None)
+            };
+            self.observer.on_iteration(&IterationStats {
+                iteration: it,
+                best: self.best_score,
+                iteration_best: Some(iter_best_score),
+                mean: Some(score_sum / self.config.n_ants as f64),
+                entropy,
+                branching,
+                diversity: None,
+            });
+        }
+        self.rng = rng;
+        self.observer.on_finish(self.history.len(), self.best_score);
+    }
+}
+
+impl Move {
+    // `pos` -- обратная перестановка тура, поддерживается в актуальном состоянии
+    fn apply(self, tour: &mut [usize], pos: &mut [usize]) {
+        let (lo, hi) = match self {
+            Move::Swap(a, b) => {
+                let (i, j) = (pos[a], pos[b]);
+                tour.swap(i, j);
+                pos[a] = j;
+                pos[b] = i;
+                return;
+            }
+            Move::Link(a, b) => {
+                let (i, j) = (pos[a], pos[b]);
+                if i < j { (i + 1, j) } else { (j, i - 1) }
+            }
+        };
+        if lo >= hi {
+            return;
+        }
+        tour[lo..=hi].reverse();
+        for (k, &city) in tour.iter().enumerate().take(hi + 1).skip(lo) {
+            pos[city] = k;
+        }
+    }
+}
+
+impl DiscretePsoTspSolver {
+    pub fn new(data: TspData, config: DiscretePsoConfig) -> Self {
         let n_cities = data.n;
-        let mut rng = rand::rng();
+        let mut rng = seeded_rng(config.seed);
+
+        let mut positions = Vec::with_capacity(config.num_particles);
+        let mut pbest_scores = Vec::with_capacity(config.num_particles);
+        let mut gbest_tour = Vec::new();
+        let mut gbest_score = f64::INFINITY;
+
+        // Начальный рой -- случайные перестановки
+        for _ in 0..config.num_particles {
+            let mut tour: Vec<usize> = (0..n_cities).collect();
+            tour.shuffle(&mut rng);
+            let score = data.calculate_tour_length(&tour);
+            if score < gbest_score {
+                gbest_score = score;
+                gbest_tour = tour.clone();
+            }
+            positions.push(tour);
+            pbest_scores.push(score);
+        }
+
+        DiscretePsoTspSolver {
+            data,
+            velocities: vec![Vec::new(); config.num_particles],
+            pbest_tours: positions.clone(),
+            positions,
+            pbest_scores,
+            gbest_tour,
+            gbest_score,
+            history: Vec::new(),
+            rng,
+            cancel: CancelToken::default(),
+            observer: Box::new(LogObserver::default()),
+            config,
+        }
+    }
+
+    fn inverse(tour: &[usize]) -> Vec<usize> {
+        let mut pos = vec![0; tour.len()];
+        for (i, &city) in tour.iter().enumerate() {
+            pos[city] = i;
+        }
+        pos
+    }
+
+    // Разность `to - from`: последовательность ходов, переводящая `from` в `to`
+    fn move_sequence(from: &[usize], to: &[usize], kind: MoveKind) -> Vec<Move> {
+        let n = from.len();
+        let mut x = from.to_vec();
+        let mut pos = Self::inverse(&x);
+
+        let mut moves = Vec::new();
+        match kind {
+            MoveKind::Swap => {
+                for i in 0..n {
+                    if x[i] != to[i] {
+                        let m = Move::Swap(x[i], to[i]);
+                        m.apply(&mut x, &mut pos);
+                        moves.push(m);
+                    }
+                }
+            }
+            MoveKind::TwoOpt => {
+                // Добавляем ребра `to` по очереди; тур замкнут, поэтому соседство проверяем по кругу
+                for i in 1..n {
+                    let (a, b) = (to[i - 1], to[i]);
+                    let p = pos[a];
+                    if x[(p + 1) % n] == b || x[(p + n - 1) % n] == b {
+                        continue;
+                    }
+                    let m = Move::Link(a, b);
+                    m.apply(&mut x, &mut pos);
+                    moves.push(m);
+                }
+            }
+        }
+        moves
+    }
+
+    pub fn run(&mut self) {
+        let params = format!("{:?} moves", self.config.moves);
+        self.observer.on_start("Discrete PSO", &params);
+        let mut rng = self.rng.clone();
+        let start = Instant::now();
+
+        for it in 0..self.config.iterations {
+            if self.cancel.is_cancelled() {
+                self.observer.on_event(it, "interrupted");
+                break;
+            }
+            if self
+                .config
+                .time_limit
+                .is_some_and(|limit| start.elapsed() >= limit)
+            {
+                self.observer.on_event(it, "time limit reached");
+                break;
+            }
+            let gbest_before = self.gbest_score;
+            let mut iter_best = f64::INFINITY;
+            let mut score_sum = 0.0;
+            for i in 0..self.config.num_particles {
+                // v = w*v + c1*r1*(pbest - x) + c2*r2*(gbest - x)
+                let r1: f64 = rng.random();
+                let r2: f64 = rng.random();
+                let mut velocity: Vec<Move> = self.velocities[i]
+                    .iter()
+                    .copied()
+                    .filter(|_| rng.random::<f64>() < self.config.w)
+                    .collect();
+
+                let to_pbest = Self::move_sequence(
+                    &self.positions[i],
+                    &self.pbest_tours[i],
+                    self.config.moves,
+                );
+                velocity.extend(
+                    to_pbest
+                        .into_iter()
+                        .filter(|_| rng.random::<f64>() < self.config.c1 * r1),
+                );
+                let to_gbest =
+                    Self::move_sequence(&self.positions[i], &self.gbest_tour, self.config.moves);
+                velocity.extend(
+                    to_gbest
+                        .into_iter()
+                        .filter(|_| rng.random::<f64>() < self.config.c2 * r2),
+                );
+
+                if self.config.max_velocity > 0 {
+                    velocity.truncate(self.config.max_velocity);
+                }
+
+                // x = x + v
+                let mut pos = Self::inverse(&self.positions[i]);
+                for &m in &velocity {
+                    m.apply(&mut self.positions[i], &mut pos);
+                }
+                self.velocities[i] = velocity;
+
+                let score = self.data.calculate_tour_length(&self.positions[i]);
+                iter_best = iter_best.min(score);
+                score_sum += score;
+                if score < self.pbest_scores[i] {
+                    self.pbest_scores[i] = score;
+                    self.pbest_tours[i] = self.positions[i].clone();
+                }
+                if score < self.gbest_score {
+                    self.gbest_score = score;
+                    self.gbest_tour = self.positions[i].clone();
+                }
+            }
+            if self.gbest_score < gbest_before {
+                self.observer
+                    .on_improvement(it, &self.gbest_tour, self.gbest_score);
+            }
+            self.history.push(self.gbest_score);
+            let mean = score_sum / self.config.num_particles as f64;
+            let diversity =
+                population_diversity(self.positions.iter().map(|p| &p[..]), &self.gbest_tour);
+            self.observer.on_iteration(&IterationStats {
+                iteration: it,
+                best: self.gbest_score,
+                iteration_best: Some(iter_best),
+                mean: Some(mean),
+                diversity: Some(diversity),
+                ..IterationStats::default()
+            });
+        }
+
+        self.rng = rng;
+        self.observer
+            .on_finish(self.history.len(), self.gbest_score);
+    }
+}
+
+impl GaTspSolver {
+    pub fn new(data: TspData, config: GaConfig) -> Self {
+        let n = data.n;
+        let mut rng = seeded_rng(config.seed);
+
+        debug!("precomputing candidate lists (top 30)");
+        let candidates = build_candidates(&data, 30);
+
+        // Начальная популяция -- случайные перестановки (после 2-opt в гибридном режиме)
+        let mut population = Vec::with_capacity(config.population);
+        for _ in 0..config.population {
+            let mut tour: Vec<usize> = (0..n).collect();
+            tour.shuffle(&mut rng);
+            if config.local_search {
+                two_opt_fast(&data, &mut tour);
+            }
+            population.push((data.calculate_tour_length(&tour), tour));
+        }
+        population.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let (best_score, best_tour) = population
+            .first()
+            .cloned()
+            .unwrap_or_else(|| identity_tour(&data));
+
+        GaTspSolver {
+            data,
+            config,
+            candidates,
+            population,
+            best_tour,
+            best_score,
+            history: Vec::new(),
+            rng,
+            cancel: CancelToken::default(),
+            observer: Box::new(LogObserver::default()),
+        }
+    }
+
+    fn tournament(&self, rng: &mut ChaCha8Rng) -> usize {
+        // Популяция отсортирована, поэтому лучший в турнире -- минимальный индекс
+        (0..self.config.tournament.max(1))
+            .map(|_| rng.random_range(0..self.population.len()))
+            .min()
+            .unwrap()
+    }
+
+    fn random_segment(n: usize, rng: &mut ChaCha8Rng) -> (usize, usize) {
+        let a = rng.random_range(0..n);
+        let b = rng.random_range(0..n);
+        (a.min(b), a.max(b))
+    }
+
+    fn order_crossover(p1: &[usize], p2: &[usize], rng: &mut ChaCha8Rng) -> Vec<usize> {
+        let n = p1.len();
+        let (i, j) = Self::random_segment(n, rng);
+        let mut child = vec![usize::MAX; n];
+        let mut used = vec![false; n];
+        for k in i..=j {
+            child[k] = p1[k];
+            used[p1[k]] = true;
+        }
+        // Остальные позиции заполняются в порядке p2, начиная после отрезка
+        let mut fill = (j + 1) % n;
+        for k in 0..n {
+            let city = p2[(j + 1 + k) % n];
+            if !used[city] {
+                child[fill] = city;
+                fill = (fill + 1) % n;
+            }
+        }
+        child
+    }
+
+    fn pmx_crossover(p1: &[usize], p2: &[usize], rng: &mut ChaCha8Rng) -> Vec<usize> {
+        let n = p1.len();
+        let (i, j) = Self::random_segment(n, rng);
+        let mut pos_p1 = vec![0; n];
+        for (k, &city) in p1.iter().enumerate() {
+            pos_p1[city] = k;
+        }
+        let in_segment = |k: usize| k >= i && k <= j;
+
+        let mut child = p2.to_vec();
+        child[i..=j].copy_from_slice(&p1[i..=j]);
+        for k in (0..n).filter(|&k| !in_segment(k)) {
+            // Идем по отображению p1[k] -> p2[k], пока город занят отрезком
+            let mut city = p2[k];
+            while in_segment(pos_p1[city]) {
+                city = p2[pos_p1[city]];
+            }
+            child[k] = city;
+        }
+        child
+    }
+
+    fn edge_recombination(&self, p1: &[usize], p2: &[usize], rng: &mut ChaCha8Rng) -> Vec<usize> {
+        let n = p1.len();
+        let mut edges: Vec<Vec<usize>> = vec![Vec::with_capacity(4); n];
+        for parent in [p1, p2] {
+            for k in 0..n {
+                let (u, v) = (parent[k], parent[(k + 1) % n]);
+                if !edges[u].contains(&v) {
+                    edges[u].push(v);
+                }
+                if !edges[v].contains(&u) {
+                    edges[v].push(u);
+                }
+            }
+        }
+
+        let mut visited = vec![false; n];
+        let mut child = Vec::with_capacity(n);
+        let mut curr = p1[0];
+        loop {
+            child.push(curr);
+            visited[curr] = true;
+            if child.len() == n {
+                break;
+            }
+            for &u in &edges[curr].clone() {
+                edges[u].retain(|&x| x != curr);
+            }
+
+            // Сосед с наименьшим числом оставшихся ребер, при равенстве -- случайный
+            let next = edges[curr]
+                .iter()
+                .copied()
+                .filter(|&v| !visited[v])
+                .map(|v| (edges[v].len(), rng.random::<u32>(), v))
+                .min()
+                .map(|(_, _, v)| v);
+            curr = match next {
+                Some(v) => v,
+                // Тупик: ближайший непосещенный из кандидатов, иначе любой
+                None => self.candidates[curr]
+                    .iter()
+                    .copied()
+                    .find(|&v| !visited[v])
+                    .unwrap_or_else(|| visited.iter().position(|&v| !v).unwrap()),
+            };
+        }
+        child
+    }
+
+    // Соседи каждой вершины в туре: [prev, next]
+    fn adjacency(tour: &[usize]) -> Vec<[usize; 2]> {
+        let n = tour.len();
+        let mut adj = vec![[0; 2]; n];
+        for k in 0..n {
+            adj[tour[k]] = [tour[(k + n - 1) % n], tour[(k + 1) % n]];
+        }
+        adj
+    }
+
+    // AB-циклы: ребра только A и только B, чередующиеся вдоль цикла.
+    // Каждый цикл -- последовательность вершин, ребро (c[0], c[1]) принадлежит A
+    fn ab_cycles(
+        adj_a: &[[usize; 2]],
+        adj_b: &[[usize; 2]],
+        rng: &mut ChaCha8Rng,
+    ) -> Vec<Vec<usize>> {
+        let n = adj_a.len();
+        let mut rem_a: Vec<Vec<usize>> = vec![Vec::with_capacity(2); n];
+        let mut rem_b: Vec<Vec<usize>> = vec![Vec::with_capacity(2); n];
+        for v in 0..n {
+            for &u in &adj_a[v] {
+                if !adj_b[v].contains(&u) {
+                    rem_a[v].push(u);
+                }
+            }
+            for &u in &adj_b[v] {
+                if !adj_a[v].contains(&u) {
+                    rem_b[v].push(u);
+                }
+            }
+        }
+
+        let take = |rem: &mut Vec<Vec<usize>>, v: usize, rng: &mut ChaCha8Rng| -> usize {
+            let k = rng.random_range(0..rem[v].len());
+            let u = rem[v].swap_remove(k);
+            let back = rem[u].iter().position(|&x| x == v).unwrap();
+            rem[u].swap_remove(back);
+            u
+        };
+
+        let mut cycles = Vec::new();
+        // Позиция вершины в текущем пути отдельно для четных и нечетных индексов
+        let mut at = vec![[usize::MAX; 2]; n];
+        for start in 0..n {
+            while !rem_a[start].is_empty() {
+                let mut path = vec![start];
+                at[start][0] = 0;
+                loop {
+                    let curr = *path.last().unwrap();
+                    let use_a = path.len() % 2 == 1;
+                    let next = if use_a {
+                        take(&mut rem_a, curr, rng)
+                    } else {
+                        take(&mut rem_b, curr, rng)
+                    };
+                    let idx = path.len();
+                    let parity = idx % 2;
+                    if at[next][parity] != usize::MAX {
+                        // Замкнули чередующийся цикл -- вырезаем его из пути
+                        let k = at[next][parity];
+                        let mut cycle: Vec<usize> = path.drain(k..).collect();
+                        for (off, &v) in cycle.iter().enumerate() {
+                            at[v][(k + off) % 2] = usize::MAX;
+                        }
+                        if parity == 1 {
+                            // Цикл начинается с B-ребра: сдвигаем, чтобы первым было A
+                            cycle.rotate_left(1);
+                        }
+                        cycles.push(cycle);
+                        // Путь продолжается из точки замыкания
+                        at[next][parity] = k;
+                        path.push(next);
+                        if path.len() == 1 {
+                            break;
+                        }
+                        continue;
+                    }
+                    at[next][parity] = idx;
+                    path.push(next);
+                }
+            }
+        }
+        cycles
+    }
+
+    fn eax_crossover(&self, p1: &[usize], p2: &[usize], rng: &mut ChaCha8Rng) -> Vec<usize> {
+        let n = p1.len();
+        let adj_a = Self::adjacency(p1);
+        let adj_b = Self::adjacency(p2);
+        let cycles = Self::ab_cycles(&adj_a, &adj_b, rng);
+        if cycles.is_empty() {
+            return p1.to_vec();
+        }
+
+        // EAX-1AB: применяем одну случайную AB-цепь к родителю A
+        let cycle = &cycles[rng.random_range(0..cycles.len())];
+        let mut adj = adj_a;
+        let m = cycle.len();
+        for k in 0..m {
+            let (u, v) = (cycle[k], cycle[(k + 1) % m]);
+            if k % 2 == 0 {
+                Self::replace_neighbour(&mut adj, u, v, usize::MAX);
+                Self::replace_neighbour(&mut adj, v, u, usize::MAX);
+            }
+        }
+        for k in (1..m).step_by(2) {
+            let (u, v) = (cycle[k], cycle[(k + 1) % m]);
+            Self::replace_neighbour(&mut adj, u, usize::MAX, v);
+            Self::replace_neighbour(&mut adj, v, usize::MAX, u);
+        }
+
+        self.merge_subtours(&mut adj);
+        Self::tour_from_adjacency(&adj, n)
+    }
+
+    fn replace_neighbour(adj: &mut [[usize; 2]], v: usize, old: usize, new: usize) {
+        let slot = if adj[v][0] == old { 0 } else { 1 };
+        adj[v][slot] = new;
+    }
+
+    // Метки подтуров для графа степени 2
+    fn subtour_labels(adj: &[[usize; 2]]) -> (Vec<usize>, Vec<usize>) {
+        let n = adj.len();
+        let mut label = vec![usize::MAX; n];
+        let mut sizes = Vec::new();
+        for s in 0..n {
+            if label[s] != usize::MAX {
+                continue;
+            }
+            let id = sizes.len();
+            let mut size = 0;
+            let (mut prev, mut curr) = (usize::MAX, s);
+            while label[curr] == usize::MAX {
+                label[curr] = id;
+                size += 1;
+                let next = if adj[curr][0] != prev {
+                    adj[curr][0]
+                } else {
+                    adj[curr][1]
+                };
+                prev = curr;
+                curr = next;
+            }
+            sizes.push(size);
+        }
+        (label, sizes)
+    }
+
+    // Жадно сливаем подтуры: для самого маленького ищем лучшую 2-opt связку с соседями
+    fn merge_subtours(&self, adj: &mut [[usize; 2]]) {
+        loop {
+            let (label, sizes) = Self::subtour_labels(adj);
+            if sizes.len() <= 1 {
+                return;
+            }
+            let smallest = (0..sizes.len()).min_by_key(|&id| sizes[id]).unwrap();
+            let members: Vec<usize> = (0..adj.len()).filter(|&v| label[v] == smallest).collect();
+
+            let mut best: Option<(f64, usize, usize, usize, usize)> = None;
+            for &u in &members {
+                for &v in self.candidates[u].iter().take(10) {
+                    if label[v] != smallest {
+                        self.consider_merge(adj, u, v, &mut best);
+                    }
+                }
+            }
+            if best.is_none() {
+                // Среди кандидатов нет чужих вершин -- перебираем все
+                for &u in &members {
+                    for v in (0..adj.len()).filter(|&v| label[v] != smallest) {
+                        self.consider_merge(adj, u, v, &mut best);
+                    }
+                }
+            }
+
+            // Удаляем (u, u2), (v, v2) и добавляем (u, v), (u2, v2)
+            let (_, u, u2, v, v2) = best.unwrap();
+            Self::replace_neighbour(adj, u, u2, v);
+            Self::replace_neighbour(adj, u2, u, v2);
+            Self::replace_neighbour(adj, v, v2, u);
+            Self::replace_neighbour(adj, v2, v, u2);
+        }
+    }
+
+    // Лучшая связка подтуров через ребро (u, v): (прирост, u, u2, v, v2)
+    fn consider_merge(
+        &self,
+        adj: &[[usize; 2]],
+        u: usize,
+        v: usize,
+        best: &mut Option<(f64, usize, usize, usize, usize)>,
+    ) {
+        for &u2 in &adj[u] {
+            for &v2 in &adj[v] {
+                let removed = self.data.dist(u, u2) + self.data.dist(v, v2);
+                // (u, v) + (u2, v2) либо (u, v2) + (u2, v)
+                let straight = self.data.dist(u, v) + self.data.dist(u2, v2) - removed;
+                let crossed = self.data.dist(u, v2) + self.data.dist(u2, v) - removed;
+                if best.is_none_or(|b| straight < b.0) {
+                    *best = Some((straight, u, u2, v, v2));
+                }
+                if best.is_none_or(|b| crossed < b.0) {
+                    *best = Some((crossed, u, u2, v2, v));
+                }
+            }
+        }
+    }
 
-        // Диапазон для инициализации координат и скоростей
-        let pos_dist = Uniform::new(0.0, 1.0).unwrap();
-        let vel_dist_scale = Uniform::new(0.0, 1.0).unwrap(); // Для скоростей
+    fn tour_from_adjacency(adj: &[[usize; 2]], n: usize) -> Vec<usize> {
+        let mut tour = Vec::with_capacity(n);
+        let (mut prev, mut curr) = (usize::MAX, 0);
+        for _ in 0..n {
+            tour.push(curr);
+            let next = if adj[curr][0] != prev {
+                adj[curr][0]
+            } else {
+                adj[curr][1]
+            };
+            prev = curr;
+            curr = next;
+        }
+        tour
+    }
 
-        // Инициализация частиц
-        let mut positions = vec![vec![0.0; n_cities]; num_particles];
-        let mut velocities = vec![vec![0.0; n_cities]; num_particles];
-        let mut pbest_pos = vec![vec![0.0; n_cities]; num_particles];
-        let mut pbest_scores = vec![f64::INFINITY; num_particles];
+    fn make_child(&self, rng: &mut ChaCha8Rng) -> Vec<usize> {
+        let p1 = &self.population[self.tournament(rng)].1;
+        let p2 = &self.population[self.tournament(rng)].1;
+        let mut child = match self.config.crossover {
+            Crossover::Order => Self::order_crossover(p1, p2, rng),
+            Crossover::Pmx => Self::pmx_crossover(p1, p2, rng),
+            Crossover::EdgeRecombination => self.edge_recombination(p1, p2, rng),
+            Crossover::Eax => self.eax_crossover(p1, p2, rng),
+        };
+
+        if rng.random_range(0.0..1.0) < self.config.mutation_rate {
+            let (i, j) = Self::random_segment(child.len(), rng);
+            child[i..=j].reverse();
+        }
+        if self.config.local_search {
+            two_opt_fast(&self.data, &mut child);
+            if or_opt(&self.data, &self.candidates, &mut child) {
+                two_opt_fast(&self.data, &mut child);
+            }
+        }
+        child
+    }
 
-        // Лучшее глобальное
-        let mut gbest_pos = vec![0.0; n_cities];
-        let mut gbest_score = f64::INFINITY;
-        let mut gbest_tour = Vec::new();
+    pub fn run(&mut self) {
+        let params = format!(
+            "{:?} crossover, population={}",
+            self.config.crossover, self.config.population
+        );
+        self.observer.on_start("GA", &params);
+        let mut rng = self.rng.clone();
+
+        // Без популяции поколения пусты: остается стартовый тур
+        let generations = if self.population.is_empty() {
+            warn!("population = 0, generations skipped");
+            0
+        } else {
+            self.config.generations
+        };
+        for it in 0..generations {
+            if self.cancel.is_cancelled() {
+                self.observer.on_event(it, "interrupted");
+                break;
+            }
+            let elite = self.config.elitism.min(self.population.len());
+            let mut next: Vec<(f64, Vec<usize>)> = self.population[..elite].to_vec();
+            while next.len() < self.config.population {
+                let child = self.make_child(&mut rng);
+                next.push((self.data.calculate_tour_length(&child), child));
+            }
+            next.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            self.population = next;
+
+            if self.population[0].0 < self.best_score {
+                self.best_score = self.population[0].0;
+                self.best_tour = self.population[0].1.clone();
+                self.observer
+                    .on_improvement(it, &self.best_tour, self.best_score);
+            }
+            self.history.push(self.best_score);
+            let mean =
+                self.population.iter().map(|p| p.0).sum::<f64>() / self.population.len() as f64;
+            let diversity = population_diversity(
+                self.population.iter().map(|p| &p.1[..]),
+                &self.population[0].1,
+            );
+            self.observer.on_iteration(&IterationStats {
+                iteration: it,
+                best: self.best_score,
+                iteration_best: Some(self.population[0].0),
+                mean: Some(mean),
+                diversity: Some(diversity),
+                ..IterationStats::default()
+            });
+        }
 
-        // Создаем начальный рой
-        for i in 0..num_particles {
-            for j in 0..n_cities {
-                positions[i][j] = pos_dist.sample(&mut rng);
-                velocities[i][j] = vel_dist_scale.sample(&mut rng);
+        self.rng = rng;
+        self.observer.on_finish(self.history.len(), self.best_score);
+    }
+}
+
+// Пробный ход отжига; применяется теми же операциями, что и в дискретном PSO и Or-opt
+#[derive(Clone, Copy)]
+enum SaMove {
+    /// 2-opt разворот (`Move::Link`)
+    TwoOpt(Move),
+    /// Перенести блок tour[i..i + len] за город на позиции `target` (`move_block`)
+    OrOpt { i: usize, len: usize, target: usize },
+}
+
+impl SaTspSolver {
+    pub fn new(data: TspData, config: SaConfig) -> Self {
+        let rng = seeded_rng(config.seed);
+        debug!("precomputing candidate lists (top 30)");
+        let candidates = build_candidates(&data, 30);
+
+        let tour = greedy_initial_tour(&data, &candidates);
+        let score = data.calculate_tour_length(&tour);
+        info!(length = score, "greedy start {:.2}", score);
+
+        let mut pos = vec![0; data.n];
+        for (i, &city) in tour.iter().enumerate() {
+            pos[city] = i;
+        }
+
+        SaTspSolver {
+            data,
+            config,
+            candidates,
+            best_tour: tour.clone(),
+            best_score: score,
+            tour,
+            pos,
+            score,
+            history: Vec::new(),
+            rng,
+            cancel: CancelToken::default(),
+            observer: Box::new(LogObserver::default()),
+        }
+    }
+
+    // 2-opt по спискам кандидатов: новое ребро (u, v), где v -- один из ближайших к u
+    fn propose_two_opt(&self, rng: &mut ChaCha8Rng) -> Option<(SaMove, f64)> {
+        let n = self.tour.len();
+        let u = rng.random_range(0..n);
+        let cands = &self.candidates[u];
+        let v = cands[rng.random_range(0..cands.len().min(10))];
+        let (a, b) = (self.pos[u].min(self.pos[v]), self.pos[u].max(self.pos[v]));
+        if b - a < 2 || (a == 0 && b == n - 1) {
+            return None;
+        }
+        // Удаляем (t[a], t[a+1]) и (t[b], t[b+1]), добавляем (t[a], t[b]) и (t[a+1], t[b+1])
+        let (p, q) = (self.tour[a], self.tour[a + 1]);
+        let (r, s) = (self.tour[b], self.tour[(b + 1) % n]);
+        let delta = self.data.dist(p, r) + self.data.dist(q, s)
+            - self.data.dist(p, q)
+            - self.data.dist(r, s);
+        Some((SaMove::TwoOpt(Move::Link(p, r)), delta))
+    }
+
+    fn propose_or_opt(&self, rng: &mut ChaCha8Rng) -> Option<(SaMove, f64)> {
+        let n = self.tour.len();
+        let len = rng.random_range(1..=3);
+        if n < len + 3 {
+            return None;
+        }
+        let i = rng.random_range(1..n - len);
+        let (first, last) = (self.tour[i], self.tour[i + len - 1]);
+        let (prev, next) = (self.tour[i - 1], self.tour[(i + len) % n]);
+
+        let cands = &self.candidates[first];
+        let after = cands[rng.random_range(0..cands.len().min(10))];
+        let k = self.pos[after];
+        if (k + 1 >= i && k < i + len) || after == prev {
+            return None;
+        }
+        let after_next = self.tour[(k + 1) % n];
+
+        let removed =
+            self.data.dist(prev, first) + self.data.dist(last, next) - self.data.dist(prev, next);
+        // Как в `or_opt`: after -> first ... last -> after_next
+        let inserted = self.data.dist(after, first) + self.data.dist(last, after_next)
+            - self.data.dist(after, after_next);
+        Some((SaMove::OrOpt { i, len, target: k }, inserted - removed))
+    }
+
+    fn apply(&mut self, mv: SaMove) {
+        match mv {
+            SaMove::TwoOpt(link) => link.apply(&mut self.tour, &mut self.pos),
+            SaMove::OrOpt { i, len, target } => {
+                move_block(&mut self.tour, &mut self.pos, i, len, target)
             }
+        }
+    }
 
-            // Находим начальный лучший путь для этой частицы
-            let current_tour = Self::get_tour(&positions[i]);
-            let current_score = data.calculate_tour_length(&current_tour);
+    fn propose(&self, rng: &mut ChaCha8Rng) -> Option<(SaMove, f64)> {
+        if rng.random_range(0.0..1.0) < self.config.or_opt_prob {
+            self.propose_or_opt(rng)
+        } else {
+            self.propose_two_opt(rng)
+        }
+    }
 
-            pbest_pos[i] = positions[i].clone();
-            pbest_scores[i] = current_score;
+    // T0 такая, что средний ухудшающий ход принимается с вероятностью 1/2
+    fn estimate_initial_temp(&self, rng: &mut ChaCha8Rng) -> f64 {
+        let mut sum = 0.0;
+        let mut count = 0;
+        for _ in 0..1000 {
+            if let Some((_, delta)) = self.propose(rng)
+                && delta > 0.0
+            {
+                sum += delta;
+                count += 1;
+            }
+        }
+        if count == 0 {
+            return 1.0;
+        }
+        (sum / count as f64) / std::f64::consts::LN_2
+    }
 
-            if current_score < gbest_score {
-                gbest_score = current_score;
-                gbest_pos = positions[i].clone();
-                gbest_tour = current_tour;
+    pub fn run(&mut self) {
+        // При n < 4 все туры одной длины (а при n <= 1 списки кандидатов пусты): ходов нет
+        if self.data.n < 4 {
+            self.observer
+                .on_start("SA", "fewer than 4 cities, no moves");
+            self.observer.on_finish(0, self.best_score);
+            return;
+        }
+        let mut rng = self.rng.clone();
+        let t0 = self
+            .config
+            .initial_temp
+            .unwrap_or_else(|| self.estimate_initial_temp(&mut rng));
+        let params = format!("T0={:.3}, {:?}", t0, self.config.cooling);
+        self.observer.on_start("SA", &params);
+
+        let mut temp = t0;
+        let mut since_improvement = 0;
+
+        for it in 0..self.config.iterations {
+            if self.cancel.is_cancelled() {
+                self.observer.on_event(it, "interrupted");
+                break;
+            }
+            let mut accepted = 0;
+            let best_before = self.best_score;
+
+            for _ in 0..self.config.moves_per_temp {
+                let Some((mv, delta)) = self.propose(&mut rng) else {
+                    continue;
+                };
+                if delta < 0.0 || rng.random_range(0.0..1.0) < (-delta / temp).exp() {
+                    self.apply(mv);
+                    self.score += delta;
+                    accepted += 1;
+                    if self.score < self.best_score - 1e-9 {
+                        // Пересчитываем, чтобы не копить ошибку округления
+                        self.score = self.data.calculate_tour_length(&self.tour);
+                        self.best_score = self.score;
+                        self.best_tour = self.tour.clone();
+                    }
+                }
+            }
+
+            if self.best_score < best_before {
+                since_improvement = 0;
+                self.observer
+                    .on_improvement(it, &self.best_tour, self.best_score);
+            } else {
+                since_improvement += 1;
             }
+
+            temp = match self.config.cooling {
+                Cooling::Geometric { alpha } => temp * alpha,
+                Cooling::Adaptive { target_acceptance } => {
+                    let progress = it as f64 / self.config.iterations as f64;
+                    let target = target_acceptance * (1.0 - progress);
+                    let rate = accepted as f64 / self.config.moves_per_temp.max(1) as f64;
+                    if rate > target {
+                        temp * 0.95
+                    } else {
+                        temp * 1.02
+                    }
+                }
+                Cooling::Reheating {
+                    alpha,
+                    patience,
+                    reheat,
+                } => {
+                    // Подогреваем только уже остывшую систему
+                    if since_improvement >= patience && temp < t0 * reheat {
+                        since_improvement = 0;
+                        // Продолжаем от лучшего тура, чтобы подогрев не терял найденное
+                        self.tour = self.best_tour.clone();
+                        for (k, &city) in self.tour.iter().enumerate() {
+                            self.pos[city] = k;
+                        }
+                        self.score = self.best_score;
+                        self.observer.on_event(it, "reheating");
+                        t0 * reheat
+                    } else {
+                        temp * alpha
+                    }
+                }
+            };
+
+            self.history.push(self.best_score);
+            // Лучший в итерации -- длина текущего тура траектории
+            self.observer.on_iteration(&IterationStats {
+                iteration: it,
+                best: self.best_score,
+                iteration_best: Some(self.score),
+                ..IterationStats::default()
+            });
         }
 
-        PsoTspSolver {
+        self.rng = rng;
+        self.observer.on_finish(self.history.len(), self.best_score);
+    }
+}
+
+impl IlsTspSolver {
+    pub fn new(data: TspData, config: IlsConfig) -> Self {
+        let rng = seeded_rng(config.seed);
+        debug!("precomputing candidate lists (top 30)");
+        let candidates = build_candidates(&data, 30);
+
+        let mut tour = greedy_initial_tour(&data, &candidates);
+        static_two_opt(&data, &mut tour);
+        or_opt(&data, &candidates, &mut tour);
+        let score = data.calculate_tour_length(&tour);
+        info!(baseline = score, "optimized baseline {:.2}", score);
+
+        IlsTspSolver {
             data,
-            num_particles,
-            iterations,
-            w,
-            c1,
-            c2,
-            positions,
-            velocities,
-            pbest_pos,
-            pbest_scores,
-            gbest_pos,
-            gbest_tour,
-            gbest_score,
+            config,
+            candidates,
+            best_tour: tour,
+            best_score: score,
             history: Vec::new(),
+            rng,
+            cancel: CancelToken::default(),
+            observer: Box::new(LogObserver::default()),
         }
     }
 
-    // Преобразование вектора приоритетов в перестановку
-    fn get_tour(position_vector: &[f64]) -> Vec<usize> {
-        let mut indexed_values: Vec<(usize, f64)> =
-            position_vector.iter().cloned().enumerate().collect();
-        // Сортируем по значению (приоритету)
-        indexed_values.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-        // Возвращаем только индексы городов в отсортированном порядке
-        indexed_values.iter().map(|&(index, _)| index).collect()
+    fn local_search(&self, tour: &mut [usize]) -> f64 {
+        two_opt_fast(&self.data, tour);
+        if or_opt(&self.data, &self.candidates, tour) {
+            two_opt_fast(&self.data, tour);
+        }
+        self.data.calculate_tour_length(tour)
+    }
+
+    // Короткие отрезки: 2-opt с окном 200 способен их потом доработать
+    fn double_bridge(tour: &mut Vec<usize>, rng: &mut ChaCha8Rng) {
+        let n = tour.len();
+        if n < 8 {
+            return;
+        }
+        let max_len = (n / 4).clamp(1, 50);
+        let l1 = rng.random_range(1..=max_len);
+        let l2 = rng.random_range(1..=max_len);
+        let l3 = rng.random_range(1..=max_len);
+        let a = rng.random_range(1..n - l1 - l2 - l3);
+        let (b, c, d) = (a + l1, a + l1 + l2, a + l1 + l2 + l3);
+        // A B C D -> A C B D
+        let mut segment = Vec::with_capacity(d - a);
+        segment.extend_from_slice(&tour[b..c]);
+        segment.extend_from_slice(&tour[a..b]);
+        segment.extend_from_slice(&tour[c..d]);
+        tour.splice(a..d, segment);
+    }
+
+    fn segment_reversal(tour: &mut [usize], segments: usize, rng: &mut ChaCha8Rng) {
+        let n = tour.len();
+        if n < 4 {
+            return;
+        }
+        let max_len = (n / 4).clamp(2, 100);
+        for _ in 0..segments {
+            let len = rng.random_range(2..=max_len);
+            let i = rng.random_range(0..n - len);
+            tour[i..i + len].reverse();
+        }
     }
 
     pub fn run(&mut self) {
-        println!("Starting Classic PSO...");
+        let params = format!(
+            "{:?} kick, {:?} acceptance, budget {:.2?}",
+            self.config.kick, self.config.acceptance, self.config.time_limit
+        );
+        self.observer.on_start("ILS", &params);
         let start = Instant::now();
-        let mut rng = rand::rng();
-        let n_cities = self.data.n;
+        let mut rng = self.rng.clone();
 
-        // Генераторы случайных чисел для r1, r2
-        let r1_dist = Uniform::new(0.0, 1.0).unwrap();
-        let r2_dist = Uniform::new(0.0, 1.0).unwrap();
+        let mut current = self.best_tour.clone();
+        let mut current_score = self.best_score;
+        let mut temp = match self.config.acceptance {
+            Acceptance::Annealing { temp_factor, .. } => {
+                temp_factor * self.best_score / self.data.n as f64
+            }
+            _ => 0.0,
+        };
+        let mut since_improvement = 0;
+
+        let mut it = 0;
+        while it < self.config.max_iterations
+            && start.elapsed() < self.config.time_limit
+            && !self.cancel.is_cancelled()
+        {
+            let mut candidate = current.clone();
+            if self.config.restart_after > 0 && since_improvement >= self.config.restart_after {
+                // Рестарт: от лучшего тура с сильным возмущением
+                candidate = self.best_tour.clone();
+                Self::segment_reversal(&mut candidate, 10, &mut rng);
+                since_improvement = 0;
+            } else {
+                match self.config.kick {
+                    Kick::DoubleBridge => Self::double_bridge(&mut candidate, &mut rng),
+                    Kick::SegmentReversal { segments } => {
+                        Self::segment_reversal(&mut candidate, segments, &mut rng)
+                    }
+                }
+            }
+            let score = self.local_search(&mut candidate);
+
+            if score < self.best_score - 1e-9 {
+                self.best_score = score;
+                self.best_tour = candidate.clone();
+                since_improvement = 0;
+                self.observer
+                    .on_improvement(it, &self.best_tour, self.best_score);
+            } else {
+                since_improvement += 1;
+            }
 
-        for it in 0..self.iterations {
-            let mut current_iter_best = f64::INFINITY;
+            let accept = match self.config.acceptance {
+                Acceptance::Better => score < current_score,
+                Acceptance::RandomWalk => true,
+                Acceptance::Annealing { alpha, .. } => {
+                    let delta = score - current_score;
+                    let ok = delta < 0.0
+                        || (temp > 0.0 && rng.random_range(0.0..1.0) < (-delta / temp).exp());
+                    temp *= alpha;
+                    ok
+                }
+            };
+            if accept {
+                current = candidate;
+                current_score = score;
+            }
 
-            // 1. Оценка каждой частицы
-            for i in 0..self.num_particles {
-                let current_tour = Self::get_tour(&self.positions[i]);
-                let current_score = self.data.calculate_tour_length(&current_tour);
+            self.history.push(self.best_score);
+            // Лучший в итерации -- новый локальный оптимум после возмущения
+            self.observer.on_iteration(&IterationStats {
+                iteration: it,
+                best: self.best_score,
+                iteration_best: Some(score),
+                ..IterationStats::default()
+            });
+            it += 1;
+        }
 
-                // Обновление PBest
-                if current_score < self.pbest_scores[i] {
-                    self.pbest_scores[i] = current_score;
-                    self.pbest_pos[i] = self.positions[i].clone();
+        if self.cancel.is_cancelled() {
+            self.observer.on_event(it, "interrupted");
+        }
+        self.rng = rng;
+        self.observer.on_finish(it, self.best_score);
+    }
+}
+
+impl AbcTspSolver {
+    pub fn new(data: TspData, config: AbcConfig) -> Self {
+        let mut rng = seeded_rng(config.seed);
+        debug!("precomputing candidate lists (top 30)");
+        let candidates = build_candidates(&data, 30);
+
+        let sources: Vec<(f64, Vec<usize>, usize)> = (0..config.food_sources)
+            .map(|_| {
+                let tour = Self::scout(&data, &candidates, &mut rng);
+                (data.calculate_tour_length(&tour), tour, 0)
+            })
+            .collect();
+        let (best_score, best_tour) = sources
+            .iter()
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+            .map_or_else(|| identity_tour(&data), |best| (best.0, best.1.clone()));
+
+        AbcTspSolver {
+            data,
+            config,
+            candidates,
+            sources,
+            best_tour,
+            best_score,
+            history: Vec::new(),
+            rng,
+            cancel: CancelToken::default(),
+            observer: Box::new(LogObserver::default()),
+        }
+    }
+
+    // Разведчик: ближайший сосед из случайной вершины + 2-opt
+    fn scout(data: &TspData, candidates: &[Vec<usize>], rng: &mut ChaCha8Rng) -> Vec<usize> {
+        let mut tour = nearest_neighbour_tour(data, candidates, rng.random_range(0..data.n));
+        two_opt_fast(data, &mut tour);
+        tour
+    }
+
+    // Соседнее решение: 2-opt ход, добавляющий ребро (u, v) из другого источника
+    // (аналог x + phi * (x - x_k) в непрерывном ABC), иначе -- из списка кандидатов.
+    // Источник меняется только при улучшении (жадный отбор)
+    fn try_neighbour(&mut self, i: usize, rng: &mut ChaCha8Rng) {
+        let n = self.data.n;
+        let k = rng.random_range(0..self.sources.len());
+        let u = rng.random_range(0..n);
+
+        let other = &self.sources[k].1;
+        let at = other.iter().position(|&c| c == u).unwrap();
+        let mut v = other[(at + 1) % n];
+
+        let tour = &self.sources[i].1;
+        let pu = tour.iter().position(|&c| c == u).unwrap();
+        if tour[(pu + 1) % n] == v || tour[(pu + n - 1) % n] == v {
+            let cands = &self.candidates[u];
+            v = cands[rng.random_range(0..cands.len().min(10))];
+        }
+        let pv = tour.iter().position(|&c| c == v).unwrap();
+        let (a, b) = (pu.min(pv), pu.max(pv));
+        if b - a < 2 || (a == 0 && b == n - 1) {
+            self.sources[i].2 += 1;
+            return;
+        }
+
+        let (p, q) = (tour[a], tour[a + 1]);
+        let (r, s) = (tour[b], tour[(b + 1) % n]);
+        let delta = self.data.dist(p, r) + self.data.dist(q, s)
+            - self.data.dist(p, q)
+            - self.data.dist(r, s);
+        let source = &mut self.sources[i];
+        if delta < -1e-9 {
+            source.1[a + 1..=b].reverse();
+            source.0 += delta;
+            source.2 = 0;
+        } else {
+            source.2 += 1;
+        }
+    }
+
+    pub fn run(&mut self) {
+        let params = format!(
+            "sources={}, limit={}",
+            self.config.food_sources, self.config.limit
+        );
+        self.observer.on_start("ABC", &params);
+        let mut rng = self.rng.clone();
+        let m = self.sources.len();
+
+        // Без источников циклы пусты: остается стартовый тур. При n < 4 все туры
+        // одной длины, а в пустых списках кандидатов нечего выбирать
+        let cycles = if m == 0 {
+            warn!("food_sources = 0, cycles skipped");
+            0
+        } else if self.data.n < 4 {
+            debug!(cities = self.data.n, "fewer than 4 cities, cycles skipped");
+            0
+        } else {
+            self.config.cycles
+        };
+        for it in 0..cycles {
+            if self.cancel.is_cancelled() {
+                self.observer.on_event(it, "interrupted");
+                break;
+            }
+            // 1. Рабочие пчелы: по одной попытке на каждый источник
+            for i in 0..m {
+                self.try_neighbour(i, &mut rng);
+            }
+
+            // 2. Наблюдатели: выбирают источники рулеткой по fitness = 1 / длина
+            let fitness: Vec<f64> = self.sources.iter().map(|s| 1.0 / s.0).collect();
+            let total: f64 = fitness.iter().sum();
+            for _ in 0..m {
+                let r = rng.random_range(0.0..1.0) * total;
+                let mut acc = 0.0;
+                let mut chosen = m - 1;
+                for (i, &f) in fitness.iter().enumerate() {
+                    acc += f;
+                    if acc >= r {
+                        chosen = i;
+                        break;
+                    }
                 }
+                self.try_neighbour(chosen, &mut rng);
+            }
 
-                // Обновление GBest
-                if current_score < self.gbest_score {
-                    self.gbest_score = current_score;
-                    self.gbest_pos = self.positions[i].clone();
-                    self.gbest_tour = current_tour;
-                    // println!("New Best at iter {}: {:.2}", it, current_score);
+            // Запоминаем лучший источник до того, как разведчики его бросят
+            for (score, tour, _) in &mut self.sources {
+                if *score < self.best_score - 1e-9 {
+                    // Пересчитываем, чтобы не копить ошибку округления
+                    *score = self.data.calculate_tour_length(tour);
+                    self.best_score = *score;
+                    self.best_tour = tour.clone();
+                    self.observer
+                        .on_improvement(it, &self.best_tour, self.best_score);
                 }
-                current_iter_best = current_iter_best.min(current_score);
             }
-            self.history.push(self.gbest_score);
 
-            // 2. Обновление скоростей и позиций
-            for i in 0..self.num_particles {
-                let r1 = r1_dist.sample(&mut rng);
-                let r2 = r2_dist.sample(&mut rng);
+            // 3. Разведчики: истощенные источники заменяются новыми
+            for i in 0..m {
+                if self.sources[i].2 > self.config.limit {
+                    let tour = Self::scout(&self.data, &self.candidates, &mut rng);
+                    self.sources[i] = (self.data.calculate_tour_length(&tour), tour, 0);
+                }
+            }
 
-                for j in 0..n_cities {
-                    // Скорость: v = w*v + c1*r1*(pbest - x) + c2*r2*(gbest - x)
-                    let velocity_component = self.w * self.velocities[i][j]
-                        + self.c1 * r1 * (self.pbest_pos[i][j] - self.positions[i][j])
-                        + self.c2 * r2 * (self.gbest_pos[j] - self.positions[i][j]);
+            self.history.push(self.best_score);
+            let iter_best = self
+                .sources
+                .iter()
+                .map(|s| s.0)
+                .fold(f64::INFINITY, f64::min);
+            let mean = self.sources.iter().map(|s| s.0).sum::<f64>() / m as f64;
+            let diversity =
+                population_diversity(self.sources.iter().map(|s| &s.1[..]), &self.best_tour);
+            self.observer.on_iteration(&IterationStats {
+                iteration: it,
+                best: self.best_score,
+                iteration_best: Some(iter_best),
+                mean: Some(mean),
+                diversity: Some(diversity),
+                ..IterationStats::default()
+            });
+        }
 
-                    self.velocities[i][j] = velocity_component;
+        self.rng = rng;
+        self.observer.on_finish(self.history.len(), self.best_score);
+    }
+}
 
-                    // Позиция: x = x + v
-                    self.positions[i][j] = self.positions[i][j] + self.velocities[i][j];
+impl FireflyTspSolver {
+    pub fn new(data: TspData, config: FireflyConfig) -> Self {
+        let mut rng = seeded_rng(config.seed);
+        let n = data.n;
 
-                    // Ограничение позиций в [0, 1] (если нужно)
-                    // self.positions[i][j] = self.positions[i][j].clamp(0.0, 1.0);
-                    // В Random Keys claming не всегда нужен, т.к. argsort работает с любыми числами
+        let mut fireflies: Vec<(f64, Vec<usize>)> = (0..config.fireflies)
+            .map(|_| {
+                let mut tour: Vec<usize> = (0..n).collect();
+                tour.shuffle(&mut rng);
+                if config.local_search {
+                    two_opt_fast(&data, &mut tour);
+                }
+                (data.calculate_tour_length(&tour), tour)
+            })
+            .collect();
+        fireflies.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let (best_score, best_tour) = fireflies
+            .first()
+            .cloned()
+            .unwrap_or_else(|| identity_tour(&data));
+
+        FireflyTspSolver {
+            data,
+            config,
+            fireflies,
+            best_tour,
+            best_score,
+            history: Vec::new(),
+            rng,
+            cancel: CancelToken::default(),
+            observer: Box::new(LogObserver::default()),
+        }
+    }
+
+    // Расстояние между светлячками -- число ребер `a`, которых нет в `b`
+    fn edge_distance(a: &[usize], b: &[usize]) -> usize {
+        let n = a.len();
+        let adj_b = GaTspSolver::adjacency(b);
+        (0..n)
+            .filter(|&k| {
+                let (u, v) = (a[k], a[(k + 1) % n]);
+                !adj_b[u].contains(&v)
+            })
+            .count()
+    }
+
+    fn random_moves(tour: &mut [usize], count: usize, rng: &mut ChaCha8Rng) {
+        let n = tour.len();
+        for _ in 0..count {
+            let a = rng.random_range(0..n);
+            let b = rng.random_range(0..n);
+            tour[a.min(b)..=a.max(b)].reverse();
+        }
+    }
+
+    pub fn run(&mut self) {
+        let params = format!(
+            "fireflies={}, gamma={}",
+            self.config.fireflies, self.config.gamma
+        );
+        self.observer.on_start("Firefly", &params);
+        let mut rng = self.rng.clone();
+        let n = self.data.n;
+        let m = self.fireflies.len();
+        let start = Instant::now();
+        let out_of_time = |limit: Option<Duration>| limit.is_some_and(|l| start.elapsed() >= l);
+
+        // Без светлячков поколения пусты: остается стартовый тур
+        let generations = if m == 0 {
+            warn!("fireflies = 0, generations skipped");
+            0
+        } else {
+            self.config.generations
+        };
+        for it in 0..generations {
+            if self.cancel.is_cancelled() {
+                self.observer.on_event(it, "interrupted");
+                break;
+            }
+            if out_of_time(self.config.time_limit) {
+                self.observer.on_event(it, "time limit reached");
+                break;
+            }
+            // Рой отсортирован: все j < i ярче светлячка i
+            for i in 0..m {
+                // Время проверяется и внутри поколения: одна пара -- уже O(n^2)
+                if out_of_time(self.config.time_limit) {
+                    break;
+                }
+                let mut tour = self.fireflies[i].1.clone();
+                let mut pos = DiscretePsoTspSolver::inverse(&tour);
+                for j in 0..i {
+                    if self.fireflies[j].0 >= self.fireflies[i].0 {
+                        continue;
+                    }
+                    if out_of_time(self.config.time_limit) {
+                        break;
+                    }
+                    let bright = &self.fireflies[j].1;
+                    let r = Self::edge_distance(&tour, bright) as f64 / n as f64;
+                    let beta = self.config.beta0 * (-self.config.gamma * r * r).exp();
+
+                    // Перенимаем долю beta ребер яркого светлячка
+                    let moves =
+                        DiscretePsoTspSolver::move_sequence(&tour, bright, MoveKind::TwoOpt);
+                    let take = (beta * moves.len() as f64).ceil() as usize;
+                    for &mv in moves.iter().take(take) {
+                        mv.apply(&mut tour, &mut pos);
+                    }
+                    Self::random_moves(&mut tour, self.config.alpha, &mut rng);
+                    pos = DiscretePsoTspSolver::inverse(&tour);
+                }
+                if i == 0 {
+                    // Самый яркий летает случайно
+                    Self::random_moves(&mut tour, self.config.alpha, &mut rng);
+                }
+                if self.config.local_search {
+                    two_opt_fast(&self.data, &mut tour);
+                }
+                let score = self.data.calculate_tour_length(&tour);
+                // Лучший светлячок не должен тускнеть
+                if i > 0 || score < self.fireflies[0].0 {
+                    self.fireflies[i] = (score, tour);
                 }
             }
-            if it % 100 == 0 {
-                println!("Iter {}: Best = {:.2}", it, self.gbest_score);
+            self.fireflies
+                .sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+            if self.fireflies[0].0 < self.best_score {
+                self.best_score = self.fireflies[0].0;
+                self.best_tour = self.fireflies[0].1.clone();
+                self.observer
+                    .on_improvement(it, &self.best_tour, self.best_score);
             }
+            self.history.push(self.best_score);
+            let mean = self.fireflies.iter().map(|f| f.0).sum::<f64>() / m as f64;
+            let diversity = population_diversity(
+                self.fireflies.iter().map(|f| &f.1[..]),
+                &self.fireflies[0].1,
+            );
+            self.observer.on_iteration(&IterationStats {
+                iteration: it,
+                best: self.best_score,
+                iteration_best: Some(self.fireflies[0].0),
+                mean: Some(mean),
+                diversity: Some(diversity),
+                ..IterationStats::default()
+            });
+        }
+
+        self.rng = rng;
+        self.observer.on_finish(self.history.len(), self.best_score);
+    }
+}
+
+impl ConstructionTspSolver {
+    pub fn new(data: TspData, config: ConstructionConfig) -> Self {
+        debug!("precomputing candidate lists (top 30)");
+        let candidates = build_candidates(&data, 30);
+
+        ConstructionTspSolver {
+            data,
+            config,
+            candidates,
+            best_tour: Vec::new(),
+            best_score: f64::INFINITY,
+            history: Vec::new(),
+            cancel: CancelToken::default(),
+            observer: Box::new(LogObserver::default()),
         }
+    }
 
-        println!(
-            "Finished in {:.2?}s. Best: {:.2}",
-            start.elapsed(),
-            self.gbest_score
+    pub fn run(&mut self) {
+        let params = format!(
+            "{:?}, local_search={}",
+            self.config.method, self.config.local_search
         );
+        self.observer.on_start("Construction", &params);
+
+        // Итерация 0 -- построение, итерация 1 -- локальный поиск
+        let mut tour = build_tour(&self.data, &self.candidates, self.config.method);
+        self.best_score = self.data.calculate_tour_length(&tour);
+        self.observer.on_improvement(0, &tour, self.best_score);
+        self.history.push(self.best_score);
+        self.observer.on_iteration(&IterationStats {
+            iteration: 0,
+            best: self.best_score,
+            ..IterationStats::default()
+        });
+
+        if self.config.local_search && tour.len() > 3 && !self.cancel.is_cancelled() {
+            two_opt_fast(&self.data, &mut tour);
+            if or_opt(&self.data, &self.candidates, &mut tour) {
+                two_opt_fast(&self.data, &mut tour);
+            }
+            self.best_score = self.data.calculate_tour_length(&tour);
+            self.observer.on_improvement(1, &tour, self.best_score);
+            self.history.push(self.best_score);
+            self.observer.on_iteration(&IterationStats {
+                iteration: 1,
+                best: self.best_score,
+                ..IterationStats::default()
+            });
+        }
+
+        self.best_tour = tour;
+        self.observer.on_finish(self.history.len(), self.best_score);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_data(n: usize, rng: &mut ChaCha8Rng) -> TspData {
+        let coords = (0..n)
+            .map(|_| (rng.random_range(0.0..1000.0), rng.random_range(0.0..1000.0)))
+            .collect();
+        TspData { coords, n }
+    }
+
+    #[test]
+    fn eax_offspring_is_permutation() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        for n in [2, 3, 5, 8, 50, 200] {
+            let data = random_data(n, &mut rng);
+            let solver = GaTspSolver::new(
+                data,
+                GaConfig {
+                    population: 8,
+                    seed: Some(n as u64),
+                    ..GaConfig::default()
+                },
+            );
+            // Родители после 2-opt из популяции и случайные перестановки
+            let mut parents: Vec<Vec<usize>> =
+                solver.population.iter().map(|(_, t)| t.clone()).collect();
+            for _ in 0..8 {
+                let mut tour: Vec<usize> = (0..n).collect();
+                tour.shuffle(&mut rng);
+                parents.push(tour);
+            }
+            for p1 in &parents {
+                for p2 in &parents {
+                    let child = solver.eax_crossover(p1, p2, &mut rng);
+                    if let Err(e) = validate_permutation(&child, n) {
+                        panic!("n = {}: {}", n, e);
+                    }
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,440 @@
+use crate::tsp_bench::trial_seeds;
+use crate::tsp_compare::{friedman, mean_ranks, wilcoxon_signed_rank};
+use crate::tsp_constructors::space_filling_curve_tour;
+use crate::tsp_data::TspData;
+use crate::tsp_observers::SolverObserver;
+use crate::tsp_solvers::{CancelToken, TspSolver};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+use std::fmt;
+use tracing::{debug, info, info_span};
+
+/// Область значений параметра
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Domain {
+    /// Целое из `min..=max`
+    Int {
+        min: i64,
+        max: i64,
+    },
+    Real {
+        min: f64,
+        max: f64,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub domain: Domain,
+}
+
+/// Пространство настраиваемых параметров; конфигурация -- значения в порядке `params`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParamSpace {
+    pub params: Vec<Param>,
+}
+
+impl ParamSpace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn int(mut self, name: &str, min: i64, max: i64) -> Self {
+        self.params.push(Param {
+            name: name.to_string(),
+            domain: Domain::Int { min, max },
+        });
+        self
+    }
+
+    pub fn real(mut self, name: &str, min: f64, max: f64) -> Self {
+        self.params.push(Param {
+            name: name.to_string(),
+            domain: Domain::Real { min, max },
+        });
+        self
+    }
+
+    /// Значение параметра `name` в конфигурации; паникует на неизвестном имени
+    pub fn get(&self, values: &[f64], name: &str) -> f64 {
+        let k = self
+            .params
+            .iter()
+            .position(|p| p.name == name)
+            .unwrap_or_else(|| panic!("unknown parameter {}", name));
+        values[k]
+    }
+
+    /// Равномерная случайная конфигурация
+    pub fn sample(&self, rng: &mut impl Rng) -> Vec<f64> {
+        self.params
+            .iter()
+            .map(|p| match p.domain {
+                Domain::Int { min, max } => rng.random_range(min..=max) as f64,
+                Domain::Real { min, max } => rng.random_range(min..=max),
+            })
+            .collect()
+    }
+
+    /// Новая конфигурация рядом с `values`: нормальный шаг с отклонением `spread`
+    /// от ширины диапазона, с обрезкой по границам
+    pub fn perturb(&self, values: &[f64], spread: f64, rng: &mut impl Rng) -> Vec<f64> {
+        self.params
+            .iter()
+            .zip(values)
+            .map(|(p, &v)| {
+                // Бокс -- Мюллер
+                let u1: f64 = rng.random_range(f64::MIN_POSITIVE..1.0);
+                let u2: f64 = rng.random_range(0.0..1.0);
+                let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+                match p.domain {
+                    Domain::Int { min, max } => {
+                        let step = z * spread * (max - min) as f64;
+                        (v + step).round().clamp(min as f64, max as f64)
+                    }
+                    Domain::Real { min, max } => (v + z * spread * (max - min)).clamp(min, max),
+                }
+            })
+            .collect()
+    }
+
+    /// `n_ants=32 q0=0.900 ...`
+    pub fn describe(&self, values: &[f64]) -> String {
+        self.params
+            .iter()
+            .zip(values)
+            .map(|(p, v)| match p.domain {
+                Domain::Int { .. } => format!("{}={}", p.name, *v as i64),
+                Domain::Real { .. } => format!("{}={:.3}", p.name, v),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Параметры гонок
+#[derive(Clone, Debug)]
+pub struct TunerConfig {
+    /// Всего запусков солвера на все гонки (повторные оценки элит берутся из кэша)
+    pub budget: usize,
+    /// Конфигураций в начале каждой гонки: элиты прошлой гонки и новые
+    pub n_candidates: usize,
+    /// Сколько лучших переходит в следующую гонку; гонка заканчивается, когда их не больше
+    pub n_elites: usize,
+    /// Блоков (экземпляр + зерно) до первой проверки на отсев. Точный критерий Вилкоксона
+    /// на k блоках не дает p меньше 2 / 2^k, поэтому при `alpha = 0.05` нужно не меньше 6
+    pub first_test: usize,
+    /// Блоков в одной гонке не больше
+    pub max_blocks: usize,
+    /// Уровень значимости для отсева
+    pub alpha: f64,
+    /// Зерно выбора конфигураций и зерен блоков
+    pub seed: u64,
+}
+
+impl Default for TunerConfig {
+    fn default() -> Self {
+        TunerConfig {
+            budget: 200,
+            n_candidates: 8,
+            n_elites: 2,
+            first_test: 6,
+            max_blocks: 20,
+            alpha: 0.05,
+            seed: 1,
+        }
+    }
+}
+
+/// Конфигурация-участник; `race` -- номер гонки, в которой она появилась
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    pub id: usize,
+    pub values: Vec<f64>,
+    pub race: usize,
+}
+
+/// Итог одной гонки
+#[derive(Clone, Debug, PartialEq)]
+pub struct Race {
+    pub candidates: usize,
+    pub blocks: usize,
+    pub eliminated: usize,
+    /// Новых запусков солвера (без кэша)
+    pub runs: usize,
+}
+
+/// Выжившая конфигурация последней гонки (прерванной -- по оцененным блокам)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Elite {
+    pub candidate: Candidate,
+    /// Средний ранг по блокам последней гонки (1 -- лучший)
+    pub mean_rank: f64,
+    /// Средняя относительная длина: длина тура / длина обхода кривой Гильберта
+    pub mean_cost: f64,
+    pub blocks: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct TuningResult {
+    pub space: ParamSpace,
+    /// Элиты от лучшей к худшей
+    pub elites: Vec<Elite>,
+    pub races: Vec<Race>,
+    pub runs: usize,
+}
+
+impl TuningResult {
+    pub fn best(&self) -> Option<&Elite> {
+        self.elites.first()
+    }
+}
+
+impl fmt::Display for TuningResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (k, race) in self.races.iter().enumerate() {
+            writeln!(
+                f,
+                "race {}: {} candidates, {} blocks, {} eliminated, {} runs",
+                k + 1,
+                race.candidates,
+                race.blocks,
+                race.eliminated,
+                race.runs
+            )?;
+        }
+        writeln!(
+            f,
+            "{:>4} {:>9} {:>9} {:>6}  config",
+            "id", "rank", "cost", "blocks"
+        )?;
+        for e in &self.elites {
+            writeln!(
+                f,
+                "{:>4} {:>9.2} {:>9.4} {:>6}  {}",
+                e.candidate.id,
+                e.mean_rank,
+                e.mean_cost,
+                e.blocks,
+                self.space.describe(&e.candidate.values)
+            )?;
+        }
+        write!(f, "{} solver runs", self.runs)
+    }
+}
+
+/// Итерированные гонки в духе irace: каждая гонка прогоняет живые конфигурации
+/// по блокам (экземпляр по кругу в случайном порядке + свое зерно). Последовательность
+/// блоков общая для всех гонок: гонка начинается с нового блока, затем повторяет уже
+/// пройденные (элиты берут их из кэша) и дальше продолжает последовательность новыми,
+/// так что каждая гонка добавляет данных и доходит до следующих экземпляров. После `first_test` блоков
+/// при значимом критерии Фридмана отсеивает конфигурации, которые по Вилкоксону
+/// хуже лучшей по среднему рангу; выжившие элиты порождают новые конфигурации
+/// следующей гонки со сужающимся разбросом. `initial` -- стартовые конфигурации
+/// (например, подобранные вручную), остальные в первой гонке случайные.
+/// Результат запуска -- длина тура, деленная на длину обхода кривой Гильберта,
+/// чтобы блоки разных экземпляров были сравнимы; солвер должен учитывать зерно,
+/// иначе кэш результатов элит не воспроизводим.
+pub fn tune(
+    space: &ParamSpace,
+    config: &TunerConfig,
+    training: &[TspData],
+    initial: &[Vec<f64>],
+    cancel: &CancelToken,
+    make_solver: impl Fn(&[f64], TspData, u64) -> Box<dyn TspSolver>,
+) -> TuningResult {
+    let mut result = TuningResult {
+        space: space.clone(),
+        elites: Vec::new(),
+        races: Vec::new(),
+        runs: 0,
+    };
+    if training.is_empty() || config.n_candidates <= config.n_elites {
+        return result;
+    }
+
+    let references: Vec<f64> = training
+        .iter()
+        .map(|data| {
+            data.calculate_tour_length(&space_filling_curve_tour(data))
+                .max(f64::MIN_POSITIVE)
+        })
+        .collect();
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    // Экземпляры блоков -- по кругу в случайном порядке
+    let mut order: Vec<usize> = (0..training.len()).collect();
+    order.shuffle(&mut rng);
+    let mut candidates: Vec<Candidate> = Vec::new();
+    // (кандидат, блок) -> относительная длина
+    let mut costs: HashMap<(usize, usize), f64> = HashMap::new();
+    let mut elites: Vec<usize> = Vec::new();
+    // Глобальная последовательность блоков (экземпляр, зерно); растет по мере надобности
+    let mut blocks: Vec<(usize, u64)> = Vec::new();
+
+    'races: while !cancel.is_cancelled() && result.runs < config.budget {
+        let race = result.races.len();
+        let spread = 0.3 * 0.7f64.powi(race as i32);
+        let mut alive = elites.clone();
+        while alive.len() < config.n_candidates {
+            let values = if race == 0 {
+                initial
+                    .get(candidates.len())
+                    .cloned()
+                    .unwrap_or_else(|| space.sample(&mut rng))
+            } else {
+                // Родитель -- элита, лучшие выбираются чаще (веса n, n - 1, ..., 1)
+                let weights: usize = (1..=elites.len()).sum();
+                let mut pick = rng.random_range(0..weights);
+                let mut parent = 0;
+                while pick >= elites.len() - parent {
+                    pick -= elites.len() - parent;
+                    parent += 1;
+                }
+                space.perturb(&candidates[elites[parent]].values, spread, &mut rng)
+            };
+            alive.push(candidates.len());
+            candidates.push(Candidate {
+                id: candidates.len(),
+                values,
+                race,
+            });
+        }
+
+        let _span = info_span!("race", race = race + 1).entered();
+        let mut log = Race {
+            candidates: alive.len(),
+            blocks: 0,
+            eliminated: 0,
+            runs: 0,
+        };
+        // Блоки гонки в порядке оценки; первый -- новый, затем уже пройденные, затем новые
+        let seen = blocks.len();
+        let mut schedule: Vec<usize> = Vec::new();
+        while log.blocks < config.max_blocks && alive.len() > config.n_elites {
+            let block = if log.blocks == 0 || log.blocks > seen {
+                blocks.len()
+            } else {
+                log.blocks - 1
+            };
+            if block == blocks.len() {
+                // Зерна SplitMix64 -- префикс одной последовательности, k-е не зависит от числа блоков
+                let seed = trial_seeds(config.seed, block + 1)[block];
+                blocks.push((order[block % training.len()], seed));
+            }
+            let (instance, seed) = blocks[block];
+            let missing: Vec<usize> = alive
+                .iter()
+                .copied()
+                .filter(|&id| !costs.contains_key(&(id, block)))
+                .collect();
+            if result.runs + missing.len() > config.budget {
+                break;
+            }
+            for &id in &missing {
+                let mut solver =
+                    make_solver(&candidates[id].values, training[instance].clone(), seed);
+                // Попытки молчат: наблюдатель -- пустой набор
+                let observers: Vec<Box<dyn SolverObserver>> = Vec::new();
+                solver.set_observer(Box::new(observers));
+                solver.set_cancel_token(cancel.clone());
+                solver.run();
+                if cancel.is_cancelled() {
+                    // Прерванный блок не учитывается, элиты -- по полностью оцененным блокам
+                    if log.blocks > 0 {
+                        result.elites =
+                            ranked_elites(&candidates, &alive, &schedule, &costs, config.n_elites);
+                    }
+                    result.races.push(log);
+                    break 'races;
+                }
+                let length = training[instance].calculate_tour_length(solver.best_tour());
+                costs.insert((id, block), length / references[instance]);
+                result.runs += 1;
+                log.runs += 1;
+            }
+            schedule.push(block);
+            log.blocks += 1;
+
+            if log.blocks >= config.first_test {
+                let matrix = cost_matrix(&alive, &schedule, &costs);
+                let ranks = mean_ranks(&matrix);
+                let best = argmin(&ranks);
+                // Для двух кандидатов Фридман сводится к критерию знаков, сразу Вилкоксон
+                if alive.len() == 2 || friedman(&matrix).p_value < config.alpha {
+                    let before = alive.len();
+                    let mut k = 0;
+                    alive.retain(|_| {
+                        let test = wilcoxon_signed_rank(&matrix[k], &matrix[best]);
+                        k += 1;
+                        !(test.p_value < config.alpha && test.statistic > test.expected)
+                    });
+                    log.eliminated += before - alive.len();
+                    debug!(
+                        block = log.blocks,
+                        alive = alive.len(),
+                        eliminated = before - alive.len(),
+                        "elimination"
+                    );
+                }
+            }
+        }
+
+        if log.blocks == 0 {
+            break;
+        }
+        result.elites = ranked_elites(&candidates, &alive, &schedule, &costs, config.n_elites);
+        elites = result.elites.iter().map(|e| e.candidate.id).collect();
+        info!(
+            candidates = log.candidates,
+            blocks = log.blocks,
+            eliminated = log.eliminated,
+            runs = log.runs,
+            best = space.describe(&result.elites[0].candidate.values),
+            "race finished"
+        );
+        result.races.push(log);
+    }
+    result
+}
+
+// Лучшие `n` живых кандидатов по среднему рангу в блоках гонки
+fn ranked_elites(
+    candidates: &[Candidate],
+    alive: &[usize],
+    blocks: &[usize],
+    costs: &HashMap<(usize, usize), f64>,
+    n: usize,
+) -> Vec<Elite> {
+    let matrix = cost_matrix(alive, blocks, costs);
+    let ranks = mean_ranks(&matrix);
+    let mut order: Vec<usize> = (0..alive.len()).collect();
+    order.sort_by(|&a, &b| ranks[a].total_cmp(&ranks[b]));
+    order
+        .into_iter()
+        .take(n.max(1))
+        .map(|k| Elite {
+            candidate: candidates[alive[k]].clone(),
+            mean_rank: ranks[k],
+            mean_cost: matrix[k].iter().sum::<f64>() / blocks.len() as f64,
+            blocks: blocks.len(),
+        })
+        .collect()
+}
+
+fn cost_matrix(
+    ids: &[usize],
+    blocks: &[usize],
+    costs: &HashMap<(usize, usize), f64>,
+) -> Vec<Vec<f64>> {
+    ids.iter()
+        .map(|&id| blocks.iter().map(|&b| costs[&(id, b)]).collect())
+        .collect()
+}
+
+fn argmin(values: &[f64]) -> usize {
+    (0..values.len())
+        .min_by(|&a, &b| values[a].total_cmp(&values[b]))
+        .unwrap_or(0)
+}